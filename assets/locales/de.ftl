@@ -0,0 +1,222 @@
+language-name = Deutsch
+
+## Main menu
+
+menu-start = Start
+menu-daily-challenge = Tägliche Herausforderung
+menu-weekly-challenge = Wöchentliche Herausforderung
+menu-achievements = Erfolge
+menu-settings = Einstellungen
+
+## Pause menu
+
+pause-abandon = Schiff aufgeben
+pause-settings = Einstellungen
+pause-export = Netzwerk exportieren
+pause-quit = Spiel beenden
+pause-continue = Weiterfliegen
+
+## Settings
+
+settings-title = Einstellungen
+settings-master-volume = Gesamtlautstärke
+settings-sfx-volume = Effektlautstärke
+settings-music-volume = Musiklautstärke
+settings-bloom = Leuchtintensität
+settings-fullscreen = Vollbild
+settings-ui-scale = UI-Skalierung
+settings-ui-scale-auto = Automatisch
+settings-percent = { NUMBER($value, maximumFractionDigits: 0) } %
+settings-amount = { NUMBER($value, minimumFractionDigits: 2, maximumFractionDigits: 2) }
+settings-reduced-motion = Reduzierte Bewegung
+settings-safe-mode = Fotosensitiver Schutzmodus
+settings-color-mode = Farben
+color-mode-default = Standard
+color-mode-deuteranopia = Deuteranopie
+color-mode-protanopia = Protanopie
+color-mode-tritanopia = Tritanopie
+color-mode-high-contrast = Hoher Kontrast
+settings-language = Sprache
+settings-on = An
+settings-off = Aus
+settings-close = Schließen
+
+## In-game display
+
+energy-debug =
+    Energie: { NUMBER($energy, minimumFractionDigits: 1, maximumFractionDigits: 1) }
+    Entfernung: { NUMBER($distance, maximumFractionDigits: 0) }
+
+## Shop
+
+shop-send-ship = Schiff starten
+shop-history = Verlauf
+shop-hidden-upgrade = ???
+shop-links =
+    { $count ->
+        [one] { $count } Lumina-Verbindung
+       *[other] { $count } Lumina-Verbindungen
+    }
+shop-upgrade =
+    { $description }
+    Stufe { $level }
+    { $cost ->
+        [one] { $cost } Lumina-Verbindung
+       *[other] { $cost } Lumina-Verbindungen
+    }
+
+upgrade-battery = Ionenspeicher erforschen
+upgrade-reflection = Lumina-Reflexion erforschen
+upgrade-propagation = Lumina-Ausbreitung erforschen
+upgrade-generation = Lumina-Erzeugung erforschen
+upgrade-links = Maximale Lumina-Verbindungen erforschen
+upgrade-burnout = Lumina-Ausbrennen erforschen
+upgrade-recovery = Lumina-Erholung erforschen
+upgrade-capacitor = Lumina-Kondensatoren erforschen
+upgrade-autopilot = Autopilot erforschen (Umschalt+Klick setzt einen Wegpunkt, verbraucht mehr Energie)
+upgrade-trajectory = Flugbahnvorhersage erforschen (zeigt, wo Bremsen jetzt anhalten würde)
+
+## Trajectory
+
+trajectory-brake-cost = Halt: { NUMBER($energy, maximumFractionDigits: 0) } Energie
+trajectory-no-stop = Anhalten nicht mehr möglich
+
+## Mutators
+
+mutators-title = Mutatoren (Credits x{ NUMBER($multiplier, minimumFractionDigits: 2, maximumFractionDigits: 2) })
+mutator-double_drain = Doppelter Lebenserhaltungsverbrauch
+mutator-double_drain-description = Die Lebenserhaltung verbraucht doppelt so viel Energie.
+mutator-no_braking = Keine Bremsen
+mutator-no_braking-description = Das Schiff kann nicht bremsen.
+mutator-link_cost = Teure Verbindungen
+mutator-link_cost-description = Jede neue Verbindung kostet Schiffsenergie.
+mutator-fog = Nebel des Krieges
+mutator-fog-description = Nur der Raum um das Schiff ist sichtbar.
+mutator-fragile_nodes = Zerbrechliche Knoten
+mutator-fragile_nodes-description = Lumina brennen doppelt so oft aus und erholen sich langsam.
+
+## Contracts
+
+contracts-title = Aufträge
+contract-offer = { $contract } (+{ $reward } Guthaben)
+contract-progress = { $contract }: { $progress }/{ $target }
+contract-complete = { $contract }: erledigt
+contract-far_links = Verbinde 5 Knoten jenseits von Entfernung 10.000
+contract-energy = Liefere 2000 Energie in einem Flug
+
+## Story
+
+story-ship-lost =
+    Schiff verloren...
+    { $count ->
+        [one] { $count } Lumina-Verbindung geschaffen
+       *[other] { $count } Lumina-Verbindungen geschaffen
+    }
+story-mutators = Mutatoren: { $mutators } (Credits x{ NUMBER($multiplier, minimumFractionDigits: 2, maximumFractionDigits: 2) })
+story-prev = Zurück
+story-next = Weiter
+story-continue = Fortfahren
+story-fallback =
+    Keine weiteren Übertragungen verfügbar.
+
+    Setzt die Forschung fort.
+story-intro =
+    In diesem Sektor des tiefen Alls haben wir ein riesiges Feld von Lumina-Knoten entdeckt. Durch intensive Forschung haben wir Schiffe gebaut, die allein von Lumina-Ionen angetrieben werden. Diese Schiffe können Verbindungen zwischen Knoten herstellen und Ionen aufnehmen, solange sie angekoppelt sind. Um unsere Durchbrüche weiter voranzutreiben, müssen wir diese Verbindungen ausbauen und verstärken.
+
+    Ein Linksklick erzeugt eine Kraft in Richtung des Mauszeigers.
+
+    Ein Rechtsklick bremst automatisch entgegen der Bewegungsrichtung.
+
+    Geh behutsam vor, die Energie ist begrenzt.
+story-recharge = Unsere Schiffe können nur Ionen aufnehmen, wenn sie sich in der Nähe eines Lumina-Knotens befinden. Wir müssen das Knüpfen neuer Verbindungen mit regelmäßigen Ladestopps an den Knoten abwägen.
+story-bursts = Unser Verständnis von Lumina steckt noch in den Kinderschuhen. Wir haben gelernt, durch das Ankoppeln eines Schiffs Ionenstöße auszulösen, und beobachtet, wie sich diese Ionen ausbreiten, bevor sie zu ihrem Ursprung zurückkehren. Der Effekt ist eindrucksvoll, aber launisch. Jeder Versuch liefert unvorhersehbare Ergebnisse. Mit weiterer Forschung sind wir jedoch zuversichtlich, die Beständigkeit verbessern zu können.
+story-shutdown = Es dürfte offensichtlich sein, dass wiederholte Ionenstöße einen Lumina-Knoten vorübergehend abschalten können. Solange er offline ist, leitet und reflektiert der Knoten weiterhin Ionen, erzeugt aber keine neuen, bis das Schiff abgekoppelt ist und einige Zeit vergangen ist. Erste Versuche, diese Abschaltung zu umgehen, sind vielversprechend, doch es ist weitere Forschung nötig.
+story-life_support = In diesem Gebiet hängt die Lebenserhaltung des Schiffs von der Entfernung zum Eintrittspunkt ab. Wir müssen die Effizienz weiter steigern, bevor wir uns weiter hinauswagen können.
+story-link_limit =
+    Unsere Technologie kann noch keine unbegrenzte Zahl von Verbindungen an einem einzelnen Lumina-Knoten aufrechterhalten. Um katastrophale Überlastungen zu verhindern, ist jeder Knoten auf eine strikte Verbindungsgrenze beschränkt. Eine Überschreitung würde zu Stabilitätseinbrüchen und einer gefährlichen Rückkopplung führen.
+
+    Wir brauchen mehr Verbindungsdaten, um unsere Forschung voranzubringen.
+story-capacitors = Mit einem so dichten Ionenspeicher kann das Schiff mehr aufnehmen, als die Lebenserhaltung je benötigen wird. Unsere Ingenieure glauben, dass der Überschuss in Kondensatoren gespeichert werden könnte, auch wenn die Ladung verfliegt, wenn sie nicht genutzt wird.
+story-large_network = Die letzte Expedition hat ein Netzwerk gewoben, größer als alles, was wir für möglich hielten. Die Sensoren haben eine schwache Resonanz zwischen weit entfernten Knoten aufgefangen, als würde das ganze Feld zu antworten beginnen.
+story-stalled = Der Vorstand ist besorgt. Die letzten Expeditionen haben kaum Verbindungsdaten geliefert. Ruht euch öfter an den Knoten aus und jagt nicht dem Horizont nach, ohne genug Ionen für den Rückweg.
+
+## History
+
+history-summary =
+    { $runs ->
+        [one] { $runs } Flug aufgezeichnet
+       *[other] { $runs } Flüge aufgezeichnet
+    }
+    Bestwertung { $score } ({ $links } Verbindungen, Entfernung { NUMBER($distance, maximumFractionDigits: 0) }, { NUMBER($duration, maximumFractionDigits: 0) } s)
+history-empty = Keine Flüge aufgezeichnet
+history-links-chart = Verbindungen pro Flug (max. { $max })
+history-credits-chart = Guthaben im Verlauf der Kampagne (max. { $max })
+history-back = Zurück
+
+## Achievements
+
+achievement-unlocked =
+    Erfolg freigeschaltet
+    { $name }
+achievements-progress = { $unlocked } von { $total } Erfolgen freigeschaltet
+achievements-back = Zurück
+
+achievement-first_link = Erstkontakt
+achievement-first_link-description = Verbinde zwei Lumina-Knoten
+achievement-links_50 = Netzwerkarchitekt
+achievement-links_50-description = Schaffe 50 Verbindungen in einem Flug
+achievement-distance_20000 = Tiefer Raum
+achievement-distance_20000-description = Erreiche die Entfernung 20.000
+achievement-capacitor_60 = Auf Reserve
+achievement-capacitor_60-description = Überlebe 60 Sekunden allein mit Kondensatorenergie
+
+## Challenges
+
+challenge-daily = Tägliche Herausforderung
+challenge-weekly = Wöchentliche Herausforderung
+challenge-result =
+    { $challenge } { $date }
+    Punkte { $score }, Platz { $rank } von { $runs }
+challenge-unranked =
+    { $challenge } { $date }
+    Nicht gewertet
+challenge-share-code =
+    Code zum Teilen
+    { $code }
+challenge-menu = Hauptmenü
+
+## Leaderboards
+
+leaderboard-campaign = Beste Kampagnenflüge
+leaderboard-entry = { $rank }. { $score } ({ $links } Verbindungen, Entfernung { NUMBER($distance, maximumFractionDigits: 0) }, { $date })
+
+## Tutorial
+
+tutorial-step = Training { $step } von { $total }
+tutorial-thrust = Halte die linke Maustaste gedrückt, um Richtung Mauszeiger zu beschleunigen. Nimm etwas Fahrt auf.
+tutorial-attach = Fliege nahe an einen blauen Lumina-Knoten heran, um dich an ihn zu koppeln.
+tutorial-link = Fliege weiter zu einem zweiten Knoten. Wechselst du die Kopplung zwischen zwei Knoten, werden sie verbunden.
+tutorial-brake = Halte die rechte Maustaste gedrückt und bremse, bis das Schiff stillsteht.
+tutorial-energy = Bleib an einem verbundenen Knoten gekoppelt und sieh zu, wie Ionen in deine Batterie zurückfließen.
+tutorial-out-of-range = Du bist außer Reichweite deines Knotens. Fliege näher heran, um Ionen aufzunehmen.
+tutorial-complete = Training abgeschlossen. Ab jetzt wird die Batterie nicht mehr aufgefüllt, viel Glück da draußen.
+tutorial-skip = Tutorial überspringen
+
+## Editor
+
+editor-status = Werkzeug: { $tool ->
+        [lumina] Lumina
+        [hazard] Gefahrenzone
+       *[link] Verbindung
+    } | { $lumina } Lumina, { $hazards } Gefahrenzonen, { $links } Verbindungen
+editor-help =
+    1/2/3: Lumina-, Gefahrenzonen- und Verbindungswerkzeug
+    Linksklick: platzieren oder ziehen, mit dem Verbindungswerkzeug zwei Lumina anklicken
+    Rechtsklick oder Entf: entfernen
+    R: ruhende Lumina umschalten, Mausrad: Größe der Gefahrenzone
+    WASD: bewegen, P: von hier spielen, F2: zurück zum Editor
+    Strg+S: speichern, Esc: Editor verlassen
+
+## Energie-Overlay
+
+energy-overlay-counts = reflektiert { $reflected } | weitergeleitet { $propagated } | abgebrochen { $terminated }
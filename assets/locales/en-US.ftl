@@ -0,0 +1,222 @@
+language-name = English
+
+## Main menu
+
+menu-start = Start
+menu-daily-challenge = Daily challenge
+menu-weekly-challenge = Weekly challenge
+menu-achievements = Achievements
+menu-settings = Settings
+
+## Pause menu
+
+pause-abandon = Abandon ship
+pause-settings = Settings
+pause-export = Export network
+pause-quit = Quit game
+pause-continue = Continue run
+
+## Settings
+
+settings-title = Settings
+settings-master-volume = Master volume
+settings-sfx-volume = Effects volume
+settings-music-volume = Music volume
+settings-bloom = Bloom intensity
+settings-fullscreen = Fullscreen
+settings-ui-scale = UI scale
+settings-ui-scale-auto = Auto
+settings-percent = { NUMBER($value, maximumFractionDigits: 0) }%
+settings-amount = { NUMBER($value, minimumFractionDigits: 2, maximumFractionDigits: 2) }
+settings-reduced-motion = Reduced motion
+settings-safe-mode = Photosensitivity safe mode
+settings-color-mode = Colors
+color-mode-default = Default
+color-mode-deuteranopia = Deuteranopia
+color-mode-protanopia = Protanopia
+color-mode-tritanopia = Tritanopia
+color-mode-high-contrast = High contrast
+settings-language = Language
+settings-on = On
+settings-off = Off
+settings-close = Close
+
+## In-game display
+
+energy-debug =
+    Energy: { NUMBER($energy, minimumFractionDigits: 1, maximumFractionDigits: 1) }
+    Distance: { NUMBER($distance, maximumFractionDigits: 0) }
+
+## Shop
+
+shop-send-ship = Send ship
+shop-history = History
+shop-hidden-upgrade = ???
+shop-links =
+    { $count ->
+        [one] { $count } lumina link
+       *[other] { $count } lumina links
+    }
+shop-upgrade =
+    { $description }
+    Level { $level }
+    { $cost ->
+        [one] { $cost } lumina link
+       *[other] { $cost } lumina links
+    }
+
+upgrade-battery = Research ion storage
+upgrade-reflection = Research Lumina reflection
+upgrade-propagation = Research Lumina propagation
+upgrade-generation = Research Lumina generation
+upgrade-links = Research max Lumina links
+upgrade-burnout = Research Lumina burnout
+upgrade-recovery = Research Lumina recovery
+upgrade-capacitor = Research Lumina capacitors
+upgrade-autopilot = Research autopilot (Shift+click to set a waypoint, uses more energy)
+upgrade-trajectory = Research trajectory prediction (shows where braking now would stop)
+
+## Trajectory
+
+trajectory-brake-cost = Stop: { NUMBER($energy, maximumFractionDigits: 0) } energy
+trajectory-no-stop = Can't stop in time
+
+## Mutators
+
+mutators-title = Mutators (credits x{ NUMBER($multiplier, minimumFractionDigits: 2, maximumFractionDigits: 2) })
+mutator-double_drain = Double life support drain
+mutator-double_drain-description = Life support draws twice as much energy.
+mutator-no_braking = No braking
+mutator-no_braking-description = The ship can't brake.
+mutator-link_cost = Costly links
+mutator-link_cost-description = Every new link drains ship energy.
+mutator-fog = Fog of war
+mutator-fog-description = Only the space near the ship is visible.
+mutator-fragile_nodes = Fragile nodes
+mutator-fragile_nodes-description = Lumina burn out twice as often and recover slowly.
+
+## Contracts
+
+contracts-title = Contracts
+contract-offer = { $contract } (+{ $reward } credits)
+contract-progress = { $contract }: { $progress }/{ $target }
+contract-complete = { $contract }: done
+contract-far_links = Link 5 nodes beyond distance 10,000
+contract-energy = Deliver 2000 energy in one run
+
+## Story
+
+story-ship-lost =
+    Ship Lost...
+    { $count ->
+        [one] { $count } lumina link created
+       *[other] { $count } lumina links created
+    }
+story-mutators = Mutators: { $mutators } (credits x{ NUMBER($multiplier, minimumFractionDigits: 2, maximumFractionDigits: 2) })
+story-prev = Prev
+story-next = Next
+story-continue = Continue
+story-fallback =
+    No further transmissions available.
+
+    Continue research.
+story-intro =
+    In this sector of deep space, we've uncovered a vast field of Lumina nodes. Through dedicated research, we've engineered vessels powered solely by Lumina ions. These ships can establish links between nodes and draw ions when connected. To push our breakthroughs even further, we must extend and reinforce these links.
+
+    Left click applies a force in the direction of the mouse pointer.
+
+    Right click applies an automatic braking force opposite the direction of motion.
+
+    Be gentle, energy is limited.
+story-recharge = Our ships can only draw ions when they are close to a Lumina node. We need to balance forging new links with regular stops at nodes to recharge.
+story-bursts = Our grasp of Lumina is still in its infancy. We've learned how to trigger ion bursts by linking a ship, and watched as those ions propagate outward before folding back to their origin. The effect is striking but fickle. Each trial yields unpredictable results. With deeper study, however, we're confident we can improve the consistency.
+story-shutdown = It should be obvious that repeated ion bursts can temporarily shut down a Lumina node. While offline, the node will still propagate and reflect ions but won't generate more until the ship is detached and some amount of time has elapsed. Early efforts to work around this shutdown are promising, but further research is needed.
+story-life_support = In this area the ship's life-support is tied to the the distance from the point of entry. We'll need to develop further efficiencies before we can venture further.
+story-link_limit =
+    Our technology can't yet sustain unlimited links from a single Lumina node. To prevent catastrophic overloads, each node is capped at a strict connection limit. Exceeding it would result in stability collapses and a dangerous feedback loop.
+
+    We need more link data to advance our research.
+story-capacitors = With ion storage this dense, the ship can hold more than its life-support will ever need. Our engineers believe the surplus could be banked in capacitors, though the charge will bleed away unless it is spent.
+story-large_network = The last expedition wove a network larger than anything we thought possible. Sensors picked up a faint resonance between distant nodes, as if the whole field were starting to answer.
+story-stalled = The board is concerned. Recent expeditions have returned almost no link data. Rest at the nodes more often, and don't chase the horizon without the ions to get back.
+
+## History
+
+history-summary =
+    { $runs ->
+        [one] { $runs } run recorded
+       *[other] { $runs } runs recorded
+    }
+    Best score { $score } ({ $links } links, distance { NUMBER($distance, maximumFractionDigits: 0) }, { NUMBER($duration, maximumFractionDigits: 0) }s)
+history-empty = No runs recorded
+history-links-chart = Links per run (max { $max })
+history-credits-chart = Credits over campaign (max { $max })
+history-back = Back
+
+## Achievements
+
+achievement-unlocked =
+    Achievement unlocked
+    { $name }
+achievements-progress = { $unlocked } of { $total } achievements unlocked
+achievements-back = Back
+
+achievement-first_link = First contact
+achievement-first_link-description = Link two Lumina nodes
+achievement-links_50 = Network architect
+achievement-links_50-description = Create 50 links in one run
+achievement-distance_20000 = Deep space
+achievement-distance_20000-description = Reach distance 20,000
+achievement-capacitor_60 = Running on reserves
+achievement-capacitor_60-description = Stay on capacitor energy alone for 60 seconds
+
+## Challenges
+
+challenge-daily = Daily challenge
+challenge-weekly = Weekly challenge
+challenge-result =
+    { $challenge } { $date }
+    Score { $score }, rank { $rank } of { $runs }
+challenge-unranked =
+    { $challenge } { $date }
+    Not ranked
+challenge-share-code =
+    Share code
+    { $code }
+challenge-menu = Main menu
+
+## Leaderboards
+
+leaderboard-campaign = Best campaign runs
+leaderboard-entry = { $rank }. { $score } ({ $links } links, { NUMBER($distance, maximumFractionDigits: 0) } distance, { $date })
+
+## Tutorial
+
+tutorial-step = Training { $step } of { $total }
+tutorial-thrust = Hold the left mouse button to thrust towards the cursor. Pick up some speed.
+tutorial-attach = Fly close to a blue Lumina node to attach to it.
+tutorial-link = Fly on to a second node. Moving your attachment between two nodes links them.
+tutorial-brake = Hold the right mouse button to brake until the ship comes to a stop.
+tutorial-energy = Stay attached to a linked node and watch ions travel back into your battery.
+tutorial-out-of-range = You are out of range of your node. Fly closer to draw ions from it.
+tutorial-complete = Training complete. From now on the battery is no longer topped up, good luck out there.
+tutorial-skip = Skip tutorial
+
+## Editor
+
+editor-status = Tool: { $tool ->
+        [lumina] Lumina
+        [hazard] Hazard
+       *[link] Link
+    } | { $lumina } Lumina, { $hazards } hazards, { $links } links
+editor-help =
+    1/2/3: Lumina, hazard and link tools
+    Left click: place or drag, with the link tool click two Lumina
+    Right click or Delete: remove
+    R: toggle resting Lumina, mouse wheel: hazard size
+    WASD: move, P: play from here, F2: back to the editor
+    Ctrl+S: save, Esc: leave the editor
+
+## Energy overlay
+
+energy-overlay-counts = reflected { $reflected } | propagated { $propagated } | terminated { $terminated }
@@ -12,6 +12,7 @@ struct CustomMaterial {
     bloom: f32,
     wave_freq: f32,
     wave_speed: f32,
+    dashes: f32,
 };
 
 @group(2) @binding(0)
@@ -50,6 +51,9 @@ fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
         color += mat.base_color * line;
     }
 
+    let dash: f32 = mix(1.0, step(0.5, fract(world_x / 80.0)), mat.dashes);
+    color *= dash;
+
     return vec4<f32>(color.xyz * mat.bloom, clamp(color.w, 0.0, 1.0));
 }
 
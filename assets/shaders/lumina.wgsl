@@ -12,6 +12,7 @@ struct CustomMaterial {
     fill_color: vec4<f32>,
     bloom: f32,
     freq: f32,
+    pattern: f32,
 };
 
 @group(2) @binding(0)
@@ -37,7 +38,9 @@ fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     let aa: f32 = 0.002;
     let edge_alpha: f32 = 1.0 - smoothstep(thickness - aa, thickness + aa, d);
 
-    let final_color: vec3<f32> = mix(mat.fill_color.xyz * mat.bloom, mat.base_color.xyz * mat.bloom, edge_alpha);
+    let hatch: f32 = step(0.5, fract((pos.x + pos.y) * 4.0)) * mat.pattern;
+    let fill: vec3<f32> = mix(mat.fill_color.xyz, mat.base_color.xyz * 0.6, hatch);
+    let final_color: vec3<f32> = mix(fill * mat.bloom, mat.base_color.xyz * mat.bloom, edge_alpha);
     let final_alpha: f32 = max(edge_alpha, select(0.0, 1.0, r < radius));
 
     return vec4<f32>(final_color, final_alpha);
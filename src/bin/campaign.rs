@@ -0,0 +1,131 @@
+//! Plays a whole campaign with a bot pilot, shopping after every run, to spot
+//! dead ends and runaway scaling in the shop economy.
+//!
+//!     cargo run --bin campaign -- --config campaign.ron --runs 100 > campaign.csv
+//!
+//! The config is a RON document, every field is optional:
+//!
+//!     (
+//!         runs: 50,
+//!         pilot: Greedy,
+//!         seed: 1,
+//!         policy: Priority(["battery", "links", "generation"]),
+//!         frame_secs: 0.05,
+//!         max_secs: 300.0,
+//!     )
+//!
+//! One CSV line per run goes to stdout. When each upgrade was first on offer
+//! and when the story ran out go to stderr.
+
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use bevy_jam_6::{
+    arg_value, headless_app, persistence,
+    plugins::{
+        bots::{Bot, Campaign, Pilot, PurchasePolicy, play_campaign, start_bot},
+        settings::Settings,
+    },
+};
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+struct Config {
+    runs: u32,
+    pilot: Pilot,
+    /// Run `n` is played with seed `seed + n`.
+    seed: u64,
+    policy: PurchasePolicy,
+    frame_secs: f32,
+    max_secs: f32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            runs: 50,
+            pilot: Pilot::Greedy,
+            seed: 0,
+            policy: PurchasePolicy::default(),
+            frame_secs: 1.0 / 20.0,
+            max_secs: 300.0,
+        }
+    }
+}
+
+fn read_config() -> Result<Config, String> {
+    let mut config = match arg_value("--config") {
+        Some(path) => {
+            let contents =
+                std::fs::read_to_string(&path).map_err(|err| format!("{path}: {err}"))?;
+            ron::from_str(&contents).map_err(|err| format!("{path}: {err}"))?
+        }
+        None => Config::default(),
+    };
+    if let Some(runs) = arg_value("--runs") {
+        config.runs = runs
+            .parse()
+            .map_err(|_| format!("invalid run count {runs}"))?;
+    }
+    Ok(config)
+}
+
+fn main() {
+    persistence::disable_saving();
+    persistence::disable_loading();
+    let config = match read_config() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+    };
+
+    let mut app = headless_app();
+    app.insert_resource(Campaign::new(config.policy.clone(), config.seed));
+    // fades would only add frames between runs
+    app.world_mut().resource_mut::<Settings>().reduced_motion = true;
+    start_bot(
+        &mut app,
+        Bot {
+            pilot: config.pilot,
+            max_secs: config.max_secs,
+        },
+        config.seed,
+        Duration::from_secs_f32(config.frame_secs),
+    );
+    let finished = play_campaign(&mut app, config.runs);
+
+    let campaign = app.world().resource::<Campaign>();
+    println!("run,links,duration,distance,earned,credits,bought,stories");
+    for (index, run) in campaign.runs.iter().enumerate() {
+        println!(
+            "{},{},{:.2},{:.1},{},{},{},{}",
+            index + 1,
+            run.report.links,
+            run.report.duration,
+            run.report.distance,
+            run.report.credits,
+            run.credits,
+            run.bought.join(";"),
+            run.stories
+        );
+    }
+    if !finished {
+        eprintln!("run {} didn't end", campaign.runs.len() + 1);
+    }
+    for (id, run) in &campaign.revealed {
+        eprintln!("{id:<12} on offer after run {run}");
+    }
+    match campaign.stories_exhausted {
+        Some(run) => eprintln!("story exhausted after run {run}"),
+        None => {
+            let unlocked = campaign.runs.last().map_or(0, |run| run.stories);
+            eprintln!(
+                "story not exhausted, {unlocked} of {} entries unlocked",
+                campaign.stories.len()
+            );
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! Plays runs with bot pilots to see how a `Scaling` and upgrade schedule
+//! balance out.
+//!
+//!     cargo run --bin simulate -- --config balance.ron --runs 1000 > runs.csv
+//!
+//! The config is a RON document, every field is optional:
+//!
+//!     (
+//!         runs: 100,
+//!         pilots: [Greedy, Explorer, Camper],
+//!         seed: 1,
+//!         scaling: (max_links: 4),
+//!         schedule: [(from_run: 50, upgrades: [("battery", 2)])],
+//!         frame_secs: 0.05,
+//!         max_secs: 300.0,
+//!     )
+//!
+//! One CSV line per run goes to stdout, percentiles per pilot to stderr.
+
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use bevy_jam_6::{
+    arg_value, headless_app, persistence,
+    plugins::{
+        bots::{Bot, BotScaling, Pilot, RunReport, finish_run, start_bot},
+        scaling::Scaling,
+        shop::UPGRADES,
+    },
+};
+
+/// Upgrades bought by the time run `from_run` starts.
+#[derive(Deserialize, Clone, Debug)]
+struct Step {
+    from_run: u32,
+    upgrades: Vec<(String, u32)>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+struct Config {
+    runs: u32,
+    pilots: Vec<Pilot>,
+    /// Run `n` is played with seed `seed + n`, the same for every pilot.
+    seed: u64,
+    scaling: Scaling,
+    schedule: Vec<Step>,
+    frame_secs: f32,
+    max_secs: f32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            runs: 100,
+            pilots: Pilot::ALL.to_vec(),
+            seed: 0,
+            scaling: Scaling::default(),
+            schedule: Vec::new(),
+            frame_secs: 1.0 / 20.0,
+            max_secs: 300.0,
+        }
+    }
+}
+
+impl Config {
+    /// Scaling of run `run`, with the upgrades of the latest step applied.
+    fn scaling(&self, run: u32) -> Result<Scaling, String> {
+        let mut scaling = self.scaling.clone();
+        let Some(step) = self
+            .schedule
+            .iter()
+            .filter(|step| step.from_run <= run)
+            .max_by_key(|step| step.from_run)
+        else {
+            return Ok(scaling);
+        };
+        for (id, level) in &step.upgrades {
+            let upgrade = UPGRADES
+                .iter()
+                .find(|upgrade| upgrade.id() == id)
+                .ok_or_else(|| format!("unknown upgrade {id}"))?;
+            for level in 1..=*level {
+                upgrade.apply(level, &mut scaling);
+            }
+        }
+        Ok(scaling)
+    }
+}
+
+fn read_config() -> Result<Config, String> {
+    let mut config = match arg_value("--config") {
+        Some(path) => {
+            let contents =
+                std::fs::read_to_string(&path).map_err(|err| format!("{path}: {err}"))?;
+            ron::from_str(&contents).map_err(|err| format!("{path}: {err}"))?
+        }
+        None => Config::default(),
+    };
+    if let Some(runs) = arg_value("--runs") {
+        config.runs = runs
+            .parse()
+            .map_err(|_| format!("invalid run count {runs}"))?;
+    }
+    Ok(config)
+}
+
+fn play(config: &Config, pilot: Pilot, run: u32) -> Result<Option<RunReport>, String> {
+    let mut app = headless_app();
+    app.insert_resource(BotScaling(config.scaling(run)?));
+    start_bot(
+        &mut app,
+        Bot {
+            pilot,
+            max_secs: config.max_secs,
+        },
+        config.seed.wrapping_add(run as u64),
+        Duration::from_secs_f32(config.frame_secs),
+    );
+    Ok(finish_run(&mut app))
+}
+
+fn percentile(sorted: &[f32], p: f32) -> f32 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    sorted[((sorted.len() - 1) as f32 * p).round() as usize]
+}
+
+fn summarise(pilot: Pilot, name: &str, mut values: Vec<f32>) {
+    values.sort_by(f32::total_cmp);
+    eprintln!(
+        "{:<10} {name:<9} p10 {:>8.1}  p50 {:>8.1}  p90 {:>8.1}",
+        pilot.id(),
+        percentile(&values, 0.1),
+        percentile(&values, 0.5),
+        percentile(&values, 0.9),
+    );
+}
+
+fn main() {
+    persistence::disable_saving();
+    persistence::disable_loading();
+    let config = match read_config() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+    };
+
+    println!("pilot,run,seed,links,duration,distance,credits");
+    for &pilot in &config.pilots {
+        let mut reports = Vec::new();
+        for run in 0..config.runs {
+            let report = match play(&config, pilot, run) {
+                Ok(Some(report)) => report,
+                Ok(None) => {
+                    eprintln!("{} run {run} didn't end", pilot.id());
+                    continue;
+                }
+                Err(err) => {
+                    eprintln!("{err}");
+                    std::process::exit(1);
+                }
+            };
+            println!(
+                "{},{run},{},{},{:.2},{:.1},{}",
+                pilot.id(),
+                config.seed.wrapping_add(run as u64),
+                report.links,
+                report.duration,
+                report.distance,
+                report.credits
+            );
+            reports.push(report);
+        }
+        summarise(
+            pilot,
+            "links",
+            reports.iter().map(|report| report.links as f32).collect(),
+        );
+        summarise(
+            pilot,
+            "duration",
+            reports.iter().map(|report| report.duration).collect(),
+        );
+        summarise(
+            pilot,
+            "credits",
+            reports.iter().map(|report| report.credits as f32).collect(),
+        );
+    }
+}
@@ -0,0 +1,269 @@
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
+use bevy::{
+    prelude::*,
+    render::{
+        RenderPlugin,
+        settings::{RenderCreation, WgpuSettings},
+    },
+    window::{ExitCondition, WindowResized},
+    winit::WinitPlugin,
+};
+use bevy_tweening::TweeningPlugin;
+use plugins::{
+    achievements::AchievementsPlugin,
+    challenge::ChallengePlugin,
+    chunks::ChunksPlugin,
+    contracts::ContractsPlugin,
+    energy::EnergyPlugin,
+    energy_display::EnergyDisplayPlugin,
+    game_loop::GameLoopPlugin,
+    graph_export::GraphExportPlugin,
+    history::HistoryPlugin,
+    leaderboard::{Leaderboard, LeaderboardPlugin},
+    localization::LocalizationPlugin,
+    main_menu::MainMenuPlugin,
+    map::MapPlugin,
+    mixer::MixerPlugin,
+    mutators::MutatorsPlugin,
+    palette::PalettePlugin,
+    pause_menu::PauseMenuPlugin,
+    replay::{Replay, ReplayPlugin},
+    scaling::ScalingPlugin,
+    settings::{Settings, SettingsPlugin},
+    ship::ShipPlugin,
+    shop::ShopPlugin,
+    story::StoryPlugin,
+    trajectory::TrajectoryPlugin,
+    tutorial::TutorialPlugin,
+};
+
+pub mod plugins {
+    pub mod achievements;
+    #[cfg(feature = "headless")]
+    pub mod bots;
+    pub mod challenge;
+    pub mod chunks;
+    #[cfg(all(feature = "inspector", debug_assertions))]
+    pub mod console;
+    pub mod contracts;
+    #[cfg(all(feature = "dev-tools", debug_assertions))]
+    pub mod editor;
+    pub mod energy;
+    pub mod energy_display;
+    #[cfg(all(feature = "dev-tools", debug_assertions))]
+    pub mod energy_overlay;
+    pub mod game_loop;
+    pub mod graph_export;
+    pub mod history;
+    pub mod leaderboard;
+    pub mod localization;
+    pub mod main_menu;
+    pub mod map;
+    pub mod mixer;
+    pub mod mutators;
+    pub mod palette;
+    pub mod pause_menu;
+    pub mod replay;
+    pub mod scaling;
+    pub mod settings;
+    pub mod ship;
+    pub mod shop;
+    pub mod story;
+    pub mod trajectory;
+    pub mod tutorial;
+}
+
+mod materials {
+    pub mod link_material;
+    pub mod lumina_material;
+}
+
+pub mod persistence;
+
+#[derive(States, Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[states(scoped_entities)]
+pub enum AppState {
+    #[default]
+    MainMenu,
+    InGame,
+    /// Map editor, only reachable in debug builds with the `dev-tools` feature.
+    Editor,
+}
+
+#[derive(SubStates, Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[source(AppState = AppState::MainMenu)]
+#[states(scoped_entities)]
+pub enum MenuState {
+    #[default]
+    Main,
+    Achievements,
+}
+
+#[derive(SubStates, Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[source(AppState = AppState::InGame)]
+#[states(scoped_entities)]
+pub enum GameState {
+    #[default]
+    Playing,
+    Story,
+    Shop,
+    History,
+    ChallengeResult,
+}
+
+#[derive(SubStates, Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[source(GameState = GameState::Playing)]
+#[states(scoped_entities)]
+pub enum GameRunState {
+    #[default]
+    // Starting,
+    Playing,
+    Paused,
+    Ending,
+}
+
+/// Value following `name` on the command line.
+pub fn arg_value(name: &str) -> Option<String> {
+    let mut args = std::env::args();
+    args.position(|arg| arg == name)?;
+    args.next()
+}
+
+/// The game without the engine plugins, shared by the windowed game and the
+/// headless replay check.
+pub fn add_game(app: &mut App) {
+    app.init_state::<AppState>()
+        .insert_resource(ClearColor(Color::BLACK))
+        .insert_resource(UiScale::default())
+        .add_sub_state::<MenuState>()
+        .add_sub_state::<GameState>()
+        .add_sub_state::<GameRunState>()
+        .add_plugins(SettingsPlugin)
+        .add_plugins(LocalizationPlugin)
+        .add_plugins(PalettePlugin)
+        .add_plugins(MainMenuPlugin)
+        .add_plugins(PauseMenuPlugin)
+        .add_plugins(GameLoopPlugin)
+        .add_plugins(TutorialPlugin)
+        .add_plugins(StoryPlugin)
+        .add_plugins(ShopPlugin)
+        .add_plugins(MutatorsPlugin)
+        .add_plugins(ContractsPlugin)
+        .add_plugins(GraphExportPlugin)
+        .add_plugins(HistoryPlugin)
+        .add_plugins(LeaderboardPlugin)
+        .add_plugins(ReplayPlugin)
+        .add_plugins(ChallengePlugin)
+        .add_plugins(AchievementsPlugin)
+        .add_plugins(ChunksPlugin)
+        .add_plugins(MapPlugin)
+        .add_plugins(ShipPlugin)
+        .add_plugins(TrajectoryPlugin)
+        .add_plugins(EnergyPlugin)
+        .add_plugins(MixerPlugin)
+        .add_plugins(EnergyDisplayPlugin)
+        .add_plugins(ScalingPlugin)
+        .add_plugins(TweeningPlugin)
+        .add_systems(Update, init_camera)
+        .add_systems(Update, resize_camera);
+    #[cfg(feature = "headless")]
+    app.add_plugins(plugins::bots::BotsPlugin);
+}
+
+/// An app running the game without a window or GPU.
+pub fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                ..default()
+            })
+            .set(RenderPlugin {
+                render_creation: RenderCreation::Automatic(WgpuSettings {
+                    backends: None,
+                    ..default()
+                }),
+                ..default()
+            })
+            .disable::<WinitPlugin>(),
+    );
+    add_game(&mut app);
+    app
+}
+
+/// Simulates the replay of every leaderboard entry again and reports entries
+/// whose score doesn't hold up. Returns the process exit code.
+pub fn verify_replays() -> i32 {
+    persistence::disable_saving();
+    let leaderboard = persistence::load::<Leaderboard>("leaderboard");
+    let mut failed = 0;
+    for entry in &leaderboard.entries {
+        let Some(replay) = persistence::try_load::<Replay>(&entry.replay) else {
+            failed += 1;
+            println!(
+                "{:?} {} score {}: replay missing, FAILED",
+                entry.mode, entry.date, entry.score,
+            );
+            continue;
+        };
+        let score = plugins::replay::simulate(headless_app(), &replay);
+        let verdict = if score == Some(entry.score) {
+            "ok"
+        } else {
+            failed += 1;
+            "FAILED"
+        };
+        println!(
+            "{:?} {} score {} replayed {}: {verdict}",
+            entry.mode,
+            entry.date,
+            entry.score,
+            score.map_or("-".to_string(), |score| score.to_string()),
+        );
+    }
+    println!(
+        "{} of {} entries verified",
+        leaderboard.entries.len() - failed,
+        leaderboard.entries.len()
+    );
+    if failed > 0 { 1 } else { 0 }
+}
+
+const WIDTH: f32 = 1280.0;
+const HEIGHT: f32 = 720.0;
+const SCALE: f32 = 1.5;
+
+fn init_camera(projection: Query<&mut Projection, Added<Camera2d>>, window: Single<&Window>) {
+    let scale_x = window.width() / WIDTH;
+    let scale_y = window.height() / HEIGHT;
+    let zoom = (scale_x.min(scale_y)).recip();
+    for mut projection in projection {
+        if let Projection::Orthographic(ref mut ortho) = *projection {
+            ortho.scale = zoom * SCALE;
+        }
+    }
+}
+
+fn resize_camera(
+    mut resize_events: EventReader<WindowResized>,
+    mut query: Query<&mut Projection, With<Camera2d>>,
+    mut ui_scale: ResMut<UiScale>,
+    settings: Res<Settings>,
+    window: Single<&Window>,
+) {
+    if resize_events.read().count() == 0 && !settings.is_changed() {
+        return;
+    }
+    let scale_x = window.width() / WIDTH;
+    let scale_y = window.height() / HEIGHT;
+    let zoom = (scale_x.min(scale_y)).recip();
+    ui_scale.0 = settings.ui_scale.unwrap_or(1.0 / zoom);
+    for mut projection in query.iter_mut() {
+        if let Projection::Orthographic(ref mut ortho) = *projection {
+            ortho.scale = zoom * SCALE;
+        }
+    }
+}
@@ -15,6 +15,10 @@ pub struct LinkMaterial {
 
     #[uniform(0)]
     pub noise_speed: f32,
+
+    /// Draws the link dashed when 1.0, marking a state without relying on color.
+    #[uniform(0)]
+    pub dashes: f32,
 }
 
 impl Material2d for LinkMaterial {
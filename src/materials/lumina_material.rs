@@ -15,6 +15,10 @@ pub struct LuminaMaterial {
 
     #[uniform(0)]
     pub freq: f32,
+
+    /// Hatches the fill when 1.0, marking a state without relying on color.
+    #[uniform(0)]
+    pub pattern: f32,
 }
 
 impl Material2d for LuminaMaterial {
@@ -0,0 +1,166 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use bevy::prelude::*;
+use serde::{Serialize, de::DeserializeOwned};
+
+// Save files are small RON documents. On native builds they live in the
+// user's data directory (or `LUMINA_SAVE_DIR` when set), on the web they
+// are kept in local storage. Exports are plain files in an `exports`
+// directory next to the saves, and aren't available on the web.
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_dir() -> Option<std::path::PathBuf> {
+    match std::env::var_os("LUMINA_SAVE_DIR") {
+        Some(dir) => Some(dir.into()),
+        None => Some(dirs::data_dir()?.join("bevy-jam-6")),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn save_path(name: &str) -> Option<std::path::PathBuf> {
+    Some(save_dir()?.join(format!("{name}.ron")))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read(name: &str) -> Option<String> {
+    std::fs::read_to_string(save_path(name)?).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write(name: &str, contents: &str) {
+    let Some(path) = save_path(name) else {
+        warn!("no save directory available, not saving {name}");
+        return;
+    };
+    write_file(&path, contents);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_file(path: &std::path::Path, contents: &str) -> bool {
+    if let Some(parent) = path.parent()
+        && let Err(err) = std::fs::create_dir_all(parent)
+    {
+        warn!("failed to create {parent:?}: {err}");
+        return false;
+    }
+    if let Err(err) = std::fs::write(path, contents) {
+        warn!("failed to write {path:?}: {err}");
+        return false;
+    }
+    true
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn delete(name: &str) {
+    let Some(path) = save_path(name) else {
+        return;
+    };
+    if let Err(err) = std::fs::remove_file(&path)
+        && err.kind() != std::io::ErrorKind::NotFound
+    {
+        warn!("failed to remove {path:?}: {err}");
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read(name: &str) -> Option<String> {
+    storage()?.get_item(name).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write(name: &str, contents: &str) {
+    if let Some(storage) = storage()
+        && storage.set_item(name, contents).is_err()
+    {
+        warn!("failed to store {name}");
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn delete(name: &str) {
+    if let Some(storage) = storage()
+        && storage.remove_item(name).is_err()
+    {
+        warn!("failed to remove {name}");
+    }
+}
+
+static SAVING_DISABLED: AtomicBool = AtomicBool::new(false);
+static LOADING_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turns `save` into a no-op, for runs that shouldn't touch the player's saves.
+pub fn disable_saving() {
+    SAVING_DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// Makes `load` return defaults, for simulations that must not depend on the
+/// player's progress.
+pub fn disable_loading() {
+    LOADING_DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// Loads a saved value, falling back to the default if it is missing or unreadable.
+pub fn load<T: DeserializeOwned + Default>(name: &str) -> T {
+    try_load(name).unwrap_or_default()
+}
+
+/// Loads a saved value, if there is a readable one.
+pub fn try_load<T: DeserializeOwned>(name: &str) -> Option<T> {
+    if LOADING_DISABLED.load(Ordering::Relaxed) {
+        return None;
+    }
+    let contents = read(name)?;
+    ron::from_str(&contents)
+        .inspect_err(|err| warn!("failed to parse saved {name}: {err}"))
+        .ok()
+}
+
+/// Writes `contents` to `file_name` in the exports directory and returns
+/// where it ended up.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn export(file_name: &str, contents: &str) -> Option<std::path::PathBuf> {
+    if SAVING_DISABLED.load(Ordering::Relaxed) {
+        return None;
+    }
+    let path = save_dir()?.join("exports").join(file_name);
+    write_file(&path, contents).then_some(path)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn export(file_name: &str, _contents: &str) -> Option<std::path::PathBuf> {
+    warn!("exports aren't supported on the web, not writing {file_name}");
+    None
+}
+
+pub fn save<T: Serialize>(name: &str, value: &T) {
+    if SAVING_DISABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    match ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default()) {
+        Ok(contents) => write(name, &contents),
+        Err(err) => warn!("failed to serialize {name}: {err}"),
+    }
+}
+
+/// Like `save`, but without the formatting, for saves nobody reads by hand.
+pub fn save_compact<T: Serialize>(name: &str, value: &T) {
+    if SAVING_DISABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    match ron::to_string(value) {
+        Ok(contents) => write(name, &contents),
+        Err(err) => warn!("failed to serialize {name}: {err}"),
+    }
+}
+
+pub fn remove(name: &str) {
+    if SAVING_DISABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    delete(name);
+}
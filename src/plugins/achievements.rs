@@ -0,0 +1,246 @@
+use bevy::{color::palettes::css, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{GameRunState, GameState, MenuState, persistence};
+
+use super::{
+    chunks::{LinkCreatedEvent, LuminaNetwork},
+    localization::Localization,
+    scaling::Scaling,
+    ship::Ship,
+};
+
+pub struct AchievementsPlugin;
+
+const ACHIEVEMENTS_SAVE: &str = "achievements";
+const TOAST_SECS: f32 = 4.0;
+
+/// Name and description are the `achievement-<id>` and
+/// `achievement-<id>-description` messages.
+pub struct Achievement {
+    pub id: &'static str,
+}
+
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement { id: "first_link" },
+    Achievement { id: "links_50" },
+    Achievement {
+        id: "distance_20000",
+    },
+    Achievement { id: "capacitor_60" },
+];
+
+/// Persistent unlock state, in the order the achievements were earned.
+#[derive(Resource, Serialize, Deserialize, Default)]
+pub struct Achievements {
+    unlocked: Vec<String>,
+}
+
+impl Achievements {
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked.iter().any(|unlocked| unlocked == id)
+    }
+}
+
+/// Sent by gameplay systems when an achievement condition is met.
+/// Conditions may be reported repeatedly, already unlocked ids are ignored.
+#[derive(Event)]
+pub struct UnlockAchievement(pub &'static str);
+
+#[derive(Resource, Default)]
+struct CapacitorTime(f32);
+
+#[derive(Component)]
+struct ToastContainer;
+
+#[derive(Component)]
+struct Toast(Timer);
+
+fn setup(mut commands: Commands) {
+    commands.insert_resource(persistence::load::<Achievements>(ACHIEVEMENTS_SAVE));
+    commands.spawn((
+        ToastContainer,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(60.0),
+            right: Val::Px(15.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(10.0),
+            ..default()
+        },
+        GlobalZIndex(50),
+    ));
+}
+
+fn setup_run(mut commands: Commands) {
+    commands.insert_resource(CapacitorTime::default());
+}
+
+fn check_links(
+    mut created: EventReader<LinkCreatedEvent>,
+    network: Res<LuminaNetwork>,
+    mut unlock: EventWriter<UnlockAchievement>,
+) {
+    if !created.is_empty() {
+        created.clear();
+        unlock.write(UnlockAchievement("first_link"));
+    }
+    if network.is_changed() && network.size >= 50 {
+        unlock.write(UnlockAchievement("links_50"));
+    }
+}
+
+fn check_ship(
+    ship: Single<(&Ship, &Transform)>,
+    time: Res<Time>,
+    scaling: Res<Scaling>,
+    mut capacitor_time: ResMut<CapacitorTime>,
+    mut unlock: EventWriter<UnlockAchievement>,
+) {
+    let (ship, transform) = ship.into_inner();
+    if transform.translation.xy().length() >= 20000.0 {
+        unlock.write(UnlockAchievement("distance_20000"));
+    }
+    if ship.energy > scaling.max_battery {
+        capacitor_time.0 += time.delta_secs();
+        if capacitor_time.0 >= 60.0 {
+            unlock.write(UnlockAchievement("capacitor_60"));
+        }
+    } else {
+        capacitor_time.0 = 0.0;
+    }
+}
+
+fn unlock_achievements(
+    mut commands: Commands,
+    mut events: EventReader<UnlockAchievement>,
+    mut achievements: ResMut<Achievements>,
+    container: Single<Entity, With<ToastContainer>>,
+    loc: Res<Localization>,
+) {
+    let mut changed = false;
+    for UnlockAchievement(id) in events.read() {
+        if achievements.is_unlocked(id) {
+            continue;
+        }
+        if !ACHIEVEMENTS.iter().any(|a| a.id == *id) {
+            warn!("unknown achievement {id}");
+            continue;
+        }
+        achievements.unlocked.push(id.to_string());
+        changed = true;
+        commands.entity(*container).with_child((
+            Toast(Timer::from_seconds(TOAST_SECS, TimerMode::Once)),
+            Node {
+                border: UiRect::all(Val::Px(1.0)),
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            BorderColor(Color::WHITE),
+            BorderRadius::all(Val::Px(5.0)),
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+            children![Text::new(loc.tr_args(
+                "achievement-unlocked",
+                &[("name", loc.tr(&format!("achievement-{id}")).into())],
+            ))],
+        ));
+    }
+    if changed {
+        persistence::save(ACHIEVEMENTS_SAVE, achievements.as_ref());
+    }
+}
+
+fn update_toasts(mut commands: Commands, time: Res<Time>, toasts: Query<(Entity, &mut Toast)>) {
+    for (entity, mut toast) in toasts {
+        if toast.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn back(_trigger: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.set_state(MenuState::Main);
+}
+
+fn setup_gallery(mut commands: Commands, achievements: Res<Achievements>, loc: Res<Localization>) {
+    commands
+        .spawn((
+            StateScoped(MenuState::Achievements),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(10.0),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(Text::new(loc.tr_args(
+                "achievements-progress",
+                &[
+                    ("unlocked", achievements.unlocked.len().into()),
+                    ("total", ACHIEVEMENTS.len().into()),
+                ],
+            )));
+            for achievement in ACHIEVEMENTS {
+                let color: Color = if achievements.is_unlocked(achievement.id) {
+                    Color::WHITE
+                } else {
+                    css::DIM_GRAY.into()
+                };
+                parent.spawn((
+                    Node {
+                        border: UiRect::all(Val::Px(1.0)),
+                        padding: UiRect::all(Val::Px(15.0)),
+                        width: Val::Px(500.0),
+                        ..default()
+                    },
+                    BorderColor(color),
+                    BorderRadius::all(Val::Px(5.0)),
+                    children![(
+                        TextColor(color),
+                        Text::new(format!(
+                            "{}\n{}",
+                            loc.tr(&format!("achievement-{}", achievement.id)),
+                            loc.tr(&format!("achievement-{}-description", achievement.id))
+                        )),
+                    )],
+                ));
+            }
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        position_type: PositionType::Absolute,
+                        bottom: Val::Px(15.0),
+                        right: Val::Px(15.0),
+                        width: Val::Px(150.0),
+                        height: Val::Px(65.0),
+                        border: UiRect::all(Val::Px(1.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BorderColor(Color::WHITE),
+                    BorderRadius::all(Val::Px(5.0)),
+                    children![(Text::new(loc.tr("achievements-back")),)],
+                ))
+                .observe(back);
+        });
+}
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<UnlockAchievement>()
+            .add_systems(Startup, setup)
+            .add_systems(OnEnter(GameState::Playing), setup_run)
+            .add_systems(
+                Update,
+                (check_links, check_ship).run_if(in_state(GameRunState::Playing)),
+            )
+            .add_systems(Update, (unlock_achievements, update_toasts))
+            .add_systems(OnEnter(MenuState::Achievements), setup_gallery);
+    }
+}
@@ -0,0 +1,458 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppState, GameRunState, GameState};
+
+use super::{
+    chunks::{Attached, Lumina, LuminaNetwork, WorldSeed},
+    game_loop::{GameData, RunStats, Simulation, setup_run},
+    localization::Localization,
+    mutators::apply_mutators,
+    scaling::Scaling,
+    ship::{ExternalInput, Ship, ShipInput},
+    shop::{UPGRADES, UpgradeLevels, UpgradeState, buy, summarise_upgrades},
+    story::{StoryAsset, StoryLog, StoryResources},
+};
+
+pub struct BotsPlugin;
+
+/// Frames waited for the story to load, 10ms apart.
+const STORY_LOAD_ATTEMPTS: u32 = 500;
+/// Frames a campaign may take between runs.
+const MAX_SHOPPING_FRAMES: u32 = 100;
+
+/// Speed bots fly at between Lumina.
+const CRUISE_SPEED: f32 = 400.0;
+/// Speed lost per second while braking with a charged battery.
+const BRAKING: f32 = 500.0;
+/// Velocity errors below this are left alone instead of thrusting. Larger
+/// than a frame of thrust at 10 FPS, so bots don't thrust back and forth.
+const SPEED_TOLERANCE: f32 = 60.0;
+/// Bots count as arrived this close to a Lumina, well within attach range.
+const ARRIVAL: f32 = 60.0;
+/// Explorers only fly to Lumina at least this much further out.
+const EXPLORE_STEP: f32 = 500.0;
+/// Links campers make before settling down.
+const CAMP_LINKS: u32 = 3;
+/// Bots that stop wait at a linked Lumina until the battery holds this many
+/// accelerations to cruise speed, enough to reach the next one and stop.
+const HOP_RESERVE: f32 = 2.0;
+/// Longest wait at one Lumina, small networks charge slower than life
+/// support drains.
+const PATIENCE_SECS: f32 = 10.0;
+
+/// Scripted strategy flying the ship in simulations.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pilot {
+    /// Flies to the closest Lumina it can link next, stopping at each one.
+    Greedy,
+    /// Heads outwards through Lumina further from the origin, never stopping.
+    Explorer,
+    /// Links a few Lumina close to the start, then stays put collecting energy.
+    Camper,
+}
+
+impl Pilot {
+    pub const ALL: &[Pilot] = &[Pilot::Greedy, Pilot::Explorer, Pilot::Camper];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Pilot::Greedy => "greedy",
+            Pilot::Explorer => "explorer",
+            Pilot::Camper => "camper",
+        }
+    }
+}
+
+/// Present while a bot flies the ship instead of the player.
+#[derive(Resource)]
+pub struct Bot {
+    pub pilot: Pilot,
+    /// Runs are abandoned after this long, campers could otherwise go on forever.
+    pub max_secs: f32,
+}
+
+/// Scaling every bot run starts with, in place of the campaign's.
+#[derive(Resource)]
+pub struct BotScaling(pub Scaling);
+
+/// Outcome of one simulated run.
+#[derive(Clone, Copy, Debug)]
+pub struct RunReport {
+    pub links: u32,
+    pub duration: f32,
+    pub distance: f32,
+    pub credits: u32,
+}
+
+/// How bots spend their credits in the shop.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub enum PurchasePolicy {
+    /// Buys the cheapest upgrade on offer until nothing is affordable.
+    #[default]
+    Cheapest,
+    /// Buys the first affordable upgrade of the list until none is, ignoring
+    /// upgrades that aren't listed.
+    Priority(Vec<String>),
+    /// Never buys anything.
+    Hoard,
+}
+
+impl PurchasePolicy {
+    fn choose(&self, offers: &[UpgradeState]) -> Option<usize> {
+        match self {
+            PurchasePolicy::Cheapest => offers
+                .iter()
+                .filter(|offer| offer.enabled)
+                .min_by_key(|offer| offer.cost)
+                .map(|offer| offer.index),
+            PurchasePolicy::Priority(ids) => ids.iter().find_map(|id| {
+                offers
+                    .iter()
+                    .find(|offer| offer.enabled && UPGRADES[offer.index].id() == id)
+                    .map(|offer| offer.index)
+            }),
+            PurchasePolicy::Hoard => None,
+        }
+    }
+}
+
+/// A run of a campaign, with what was done with its credits.
+#[derive(Clone, Debug)]
+pub struct CampaignRun {
+    pub report: RunReport,
+    /// Credits left after shopping.
+    pub credits: u32,
+    /// Ids of the upgrades bought after the run, one per level.
+    pub bought: Vec<&'static str>,
+    /// Story entries unlocked by the end of the run.
+    pub stories: usize,
+}
+
+/// Present while a bot plays a whole campaign, going through the story and
+/// the shop after every run.
+#[derive(Resource)]
+pub struct Campaign {
+    pub policy: PurchasePolicy,
+    /// Run `n` is played with seed `seed + n`.
+    pub seed: u64,
+    pub runs: Vec<CampaignRun>,
+    /// Upgrade ids with the run after which they were first on offer.
+    pub revealed: Vec<(&'static str, u32)>,
+    /// Ids of every story entry, in file order.
+    pub stories: Vec<String>,
+    /// Run after which every story entry was unlocked.
+    pub stories_exhausted: Option<u32>,
+    credits_before: u32,
+}
+
+impl Campaign {
+    pub fn new(policy: PurchasePolicy, seed: u64) -> Self {
+        Campaign {
+            policy,
+            seed,
+            runs: Vec::new(),
+            revealed: Vec::new(),
+            stories: Vec::new(),
+            stories_exhausted: None,
+            credits_before: 0,
+        }
+    }
+}
+
+/// Input that flies towards `target`, slowing down to stop on it if `stop`.
+fn steer(position: Vec2, velocity: Vec2, target: Vec2, stop: bool) -> ShipInput {
+    let offset = target - position;
+    let distance = offset.length();
+    let speed = velocity.length();
+    if stop && (distance < ARRIVAL || speed * speed / (2.0 * BRAKING) >= distance - ARRIVAL) {
+        return ShipInput {
+            brake: speed > 0.0,
+            ..default()
+        };
+    }
+    let error = offset.normalize_or_zero() * CRUISE_SPEED - velocity;
+    if error.length() < SPEED_TOLERANCE {
+        return ShipInput::default();
+    }
+    ShipInput {
+        thrust: Some(error.normalize()),
+        ..default()
+    }
+}
+
+fn fly(
+    bot: Res<Bot>,
+    ship: Single<(&Transform, &Ship, Option<&Attached>)>,
+    lumina: Query<(Entity, &Transform, &Lumina)>,
+    network: Res<LuminaNetwork>,
+    stats: Res<RunStats>,
+    scaling: Res<Scaling>,
+    time: Res<Time>,
+    mut waited: Local<(Option<Entity>, f32)>,
+    mut input: ResMut<ShipInput>,
+) {
+    if stats.duration > bot.max_secs {
+        *input = ShipInput {
+            abandon: true,
+            ..default()
+        };
+        return;
+    }
+    let (transform, ship, attached) = ship.into_inner();
+    let position = transform.translation.xy();
+    let current = attached.map(|attached| attached.lumina);
+    let current_targets = current
+        .and_then(|entity| lumina.get(entity).ok())
+        .map(|(_, _, lumina)| lumina.targets.clone())
+        .unwrap_or_default();
+    let linkable = |entity: Entity, other: &Lumina| {
+        Some(entity) != current
+            && other.targets.len() < scaling.max_links
+            && !current_targets.contains(&entity)
+    };
+    let nearest = |filter: &dyn Fn(Entity, Vec2, &Lumina) -> bool| {
+        lumina
+            .iter()
+            .map(|(entity, transform, other)| (entity, transform.translation.xy(), other))
+            .filter(|(entity, at, other)| filter(*entity, *at, other))
+            .min_by(|a, b| a.1.distance(position).total_cmp(&b.1.distance(position)))
+            .map(|(_, at, _)| at)
+    };
+    let current_position = current
+        .and_then(|entity| lumina.get(entity).ok())
+        .map(|(_, transform, _)| transform.translation.xy());
+    // energy only arrives along links
+    if waited.0 != current {
+        *waited = (current, 0.0);
+    }
+    let recharging = !current_targets.is_empty()
+        && ship.energy < HOP_RESERVE * CRUISE_SPEED * scaling.energy_per_force
+        && waited.1 < PATIENCE_SECS;
+    if recharging {
+        waited.1 += time.delta_secs();
+    }
+    let (target, stop) = match bot.pilot {
+        Pilot::Camper if network.size >= CAMP_LINKS => (current_position, true),
+        Pilot::Greedy | Pilot::Camper if recharging && current_position.is_some() => {
+            (current_position, true)
+        }
+        Pilot::Greedy | Pilot::Camper => {
+            (nearest(&|entity, _, other| linkable(entity, other)), true)
+        }
+        Pilot::Explorer => {
+            let frontier = position.length() + EXPLORE_STEP;
+            (
+                // turning around costs as much as stopping
+                nearest(&|entity, at, other| {
+                    linkable(entity, other)
+                        && at.length() > frontier
+                        && (at - position).dot(ship.linear) >= 0.0
+                }),
+                false,
+            )
+        }
+    };
+    // with nothing in reach, head outwards where new chunks appear
+    let target = target.unwrap_or_else(|| {
+        position + position.try_normalize().unwrap_or(Vec2::X) * CRUISE_SPEED * 10.0
+    });
+    *input = steer(position, ship.linear, target, stop);
+}
+
+fn load_scaling(bot_scaling: Res<BotScaling>, mut scaling: ResMut<Scaling>) {
+    *scaling = bot_scaling.0.clone();
+}
+
+fn report(world: &World, credits_before: u32) -> RunReport {
+    let stats = world.resource::<RunStats>();
+    RunReport {
+        links: world.resource::<LuminaNetwork>().size,
+        duration: stats.duration,
+        distance: stats.max_distance,
+        credits: world.resource::<GameData>().network_credits - credits_before,
+    }
+}
+
+fn record_run(world: &mut World) {
+    let credits_before = world.resource::<Campaign>().credits_before;
+    let report = report(world, credits_before);
+    world.resource_mut::<Campaign>().runs.push(CampaignRun {
+        report,
+        credits: 0,
+        bought: Vec::new(),
+        stories: 0,
+    });
+}
+
+fn skip_story(mut commands: Commands) {
+    commands.set_state(GameState::Shop);
+}
+
+fn go_shopping(
+    mut commands: Commands,
+    mut campaign: ResMut<Campaign>,
+    mut scaling: ResMut<Scaling>,
+    mut data: ResMut<GameData>,
+    mut levels: ResMut<UpgradeLevels>,
+    mut seed: ResMut<WorldSeed>,
+    loc: Res<Localization>,
+    log: Res<StoryLog>,
+    resources: Res<StoryResources>,
+    stories: Res<Assets<StoryAsset>>,
+) {
+    let campaign = campaign.as_mut();
+    let runs = data.runs;
+    let mut offers = summarise_upgrades(&scaling, &data, &levels, &loc);
+    for offer in offers.iter().filter(|offer| !offer.hidden) {
+        let id = UPGRADES[offer.index].id();
+        if !campaign
+            .revealed
+            .iter()
+            .any(|(revealed, _)| *revealed == id)
+        {
+            campaign.revealed.push((id, runs));
+        }
+    }
+    let mut bought = Vec::new();
+    while let Some(index) = campaign.policy.choose(&offers) {
+        buy(index, &mut scaling, &mut data, &mut levels);
+        bought.push(UPGRADES[index].id());
+        offers = summarise_upgrades(&scaling, &data, &levels, &loc);
+    }
+
+    if campaign.stories.is_empty()
+        && let Some(story) = stories.get(&resources.story)
+    {
+        campaign.stories = story.entries.iter().map(|entry| entry.id.clone()).collect();
+    }
+    let unlocked = log.unlocked().len();
+    if campaign.stories_exhausted.is_none()
+        && !campaign.stories.is_empty()
+        && unlocked >= campaign.stories.len()
+    {
+        campaign.stories_exhausted = Some(runs);
+    }
+    if let Some(run) = campaign.runs.last_mut() {
+        run.credits = data.network_credits;
+        run.bought = bought;
+        run.stories = unlocked;
+    }
+
+    campaign.credits_before = data.network_credits;
+    seed.fixed = Some(campaign.seed.wrapping_add(runs as u64));
+    commands.set_state(GameState::Playing);
+}
+
+fn story_loaded(app: &App) -> bool {
+    let world = app.world();
+    world
+        .get_resource::<StoryResources>()
+        .is_some_and(|resources| {
+            world
+                .resource::<AssetServer>()
+                .is_loaded_with_dependencies(&resources.story)
+        })
+}
+
+fn run_ended(app: &App) -> bool {
+    app.world()
+        .get_resource::<State<GameRunState>>()
+        .is_some_and(|state| *state.get() == GameRunState::Ending)
+}
+
+/// Starts `app`, which must not have run yet, with a bot at the controls and
+/// the game set to `seed`.
+pub fn start_bot(app: &mut App, bot: Bot, seed: u64, frame: Duration) {
+    app.world_mut().resource_mut::<WorldSeed>().fixed = Some(seed);
+    app.insert_resource(ExternalInput)
+        .insert_resource(bot)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(frame));
+    app.finish();
+    app.cleanup();
+    app.update();
+    // runs are over in a few milliseconds, long before the story would load
+    for _ in 0..STORY_LOAD_ATTEMPTS {
+        if story_loaded(app) {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+        app.update();
+    }
+    app.world_mut()
+        .resource_mut::<NextState<AppState>>()
+        .set(AppState::InGame);
+}
+
+/// Plays the run that is starting until it ends and reports how it went.
+/// Returns `None` if it doesn't end in time, which only happens when the bot
+/// can't abandon the ship.
+pub fn finish_run(app: &mut App) -> Option<RunReport> {
+    let frame = match app.world().resource::<TimeUpdateStrategy>() {
+        TimeUpdateStrategy::ManualDuration(frame) => frame.as_secs_f32(),
+        _ => return None,
+    };
+    let credits_before = app.world().resource::<GameData>().network_credits;
+    let max_frames = (app.world().resource::<Bot>().max_secs / frame) as u32 + 60;
+    for _ in 0..max_frames {
+        app.update();
+        if run_ended(app) {
+            return Some(report(app.world(), credits_before));
+        }
+    }
+    None
+}
+
+/// Plays the campaign that is starting for `runs` runs, shopping after the
+/// last one too. Returns `false` if a run doesn't end in time.
+pub fn play_campaign(app: &mut App, runs: u32) -> bool {
+    for _ in 0..runs {
+        if finish_run(app).is_none() {
+            return false;
+        }
+        // through the story and the shop until the next run has started
+        for _ in 0..MAX_SHOPPING_FRAMES {
+            app.update();
+            if app
+                .world()
+                .get_resource::<State<GameRunState>>()
+                .is_some_and(|state| *state.get() == GameRunState::Playing)
+            {
+                break;
+            }
+        }
+    }
+    true
+}
+
+impl Plugin for BotsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Playing),
+            load_scaling
+                .run_if(resource_exists::<BotScaling>)
+                .before(apply_mutators)
+                .before(setup_run),
+        )
+        .add_systems(
+            Update,
+            fly.in_set(Simulation::Input)
+                .run_if(in_state(GameRunState::Playing))
+                .run_if(resource_exists::<Bot>),
+        )
+        .add_systems(
+            OnEnter(GameRunState::Ending),
+            record_run.run_if(resource_exists::<Campaign>),
+        )
+        .add_systems(
+            OnEnter(GameState::Story),
+            skip_story.run_if(resource_exists::<Campaign>),
+        )
+        .add_systems(
+            OnEnter(GameState::Shop),
+            go_shopping.run_if(resource_exists::<Campaign>),
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use web_time::{SystemTime, UNIX_EPOCH};
+
+use crate::{AppState, GameState};
+
+use super::{
+    chunks::WorldSeed,
+    leaderboard::{LEADERBOARD_SHOWN, LatestEntry, Leaderboard, entry_lines},
+    localization::Localization,
+    scaling::Scaling,
+    shop::UPGRADES,
+};
+
+pub struct ChallengePlugin;
+
+/// Upgrades the loadout is drawn from. Fixed, so that new upgrades don't
+/// change the loadout of challenges already played.
+const LOADOUT_UPGRADES: &[&str] = &[
+    "battery",
+    "reflection",
+    "propagation",
+    "generation",
+    "links",
+    "burnout",
+    "recovery",
+    "capacitor",
+];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChallengeKind {
+    Daily,
+    Weekly,
+}
+
+impl ChallengeKind {
+    pub const ALL: &[ChallengeKind] = &[ChallengeKind::Daily, ChallengeKind::Weekly];
+
+    /// Upgrades bought at random for the fixed loadout.
+    fn upgrades(self) -> u32 {
+        match self {
+            ChallengeKind::Daily => 8,
+            ChallengeKind::Weekly => 16,
+        }
+    }
+
+    fn code(self) -> char {
+        match self {
+            ChallengeKind::Daily => 'D',
+            ChallengeKind::Weekly => 'W',
+        }
+    }
+
+    /// Suffix of the `challenge-<id>` message naming this kind.
+    pub fn id(self) -> &'static str {
+        match self {
+            ChallengeKind::Daily => "daily",
+            ChallengeKind::Weekly => "weekly",
+        }
+    }
+}
+
+/// The challenge the current game is played as. Present from choosing it in the
+/// main menu until the game is left, and absent for regular games.
+#[derive(Resource, Clone, Debug)]
+pub struct Challenge {
+    pub kind: ChallengeKind,
+    /// First day of the challenge period, in days since the Unix epoch.
+    pub day: i64,
+    pub seed: u64,
+}
+
+impl Challenge {
+    pub fn for_day(kind: ChallengeKind, today: i64) -> Self {
+        let day = match kind {
+            ChallengeKind::Daily => today,
+            // weeks start on Monday, the epoch was a Thursday
+            ChallengeKind::Weekly => today - (today + 3).rem_euclid(7),
+        };
+        Self {
+            kind,
+            day,
+            seed: hash(&[kind as u64, day as u64]),
+        }
+    }
+
+    pub fn today(kind: ChallengeKind) -> Self {
+        Self::for_day(kind, current_day())
+    }
+
+    pub fn date(&self) -> String {
+        format_day(self.day)
+    }
+
+    /// The loadout everyone plays the challenge with, independent of the
+    /// player's own upgrades.
+    pub fn scaling(&self) -> Scaling {
+        let mut scaling = Scaling::default();
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut levels = [0; LOADOUT_UPGRADES.len()];
+        for _ in 0..self.kind.upgrades() {
+            let index = rng.random_range(0..LOADOUT_UPGRADES.len());
+            let upgrade = UPGRADES
+                .iter()
+                .find(|upgrade| upgrade.id() == LOADOUT_UPGRADES[index])
+                .expect("loadout upgrades exist");
+            if upgrade.max_level().is_some_and(|max| levels[index] >= max) {
+                continue;
+            }
+            levels[index] += 1;
+            upgrade.apply(levels[index], &mut scaling);
+        }
+        scaling
+    }
+
+    /// Code to share a result, `<D|W><yyyymmdd>-<seed>-<score>-<check>`.
+    pub fn share_code(&self, score: u32) -> String {
+        let body = format!(
+            "{}{}-{:016X}-{}",
+            self.kind.code(),
+            self.date().replace('-', ""),
+            self.seed,
+            score
+        );
+        let check = hash(&[self.seed, self.day as u64, score as u64]) & 0xffff;
+        format!("{body}-{check:04X}")
+    }
+}
+
+/// FNV-1a over the little endian bytes of the values.
+fn hash(values: &[u64]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in values.iter().flat_map(|value| value.to_le_bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Days since the Unix epoch.
+pub fn current_day() -> i64 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    (secs / 86400) as i64
+}
+
+/// `YYYY-MM-DD` for a day number.
+pub fn format_day(day: i64) -> String {
+    let (year, month, day) = civil_from_days(day);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Gregorian date for a day number, from Howard Hinnant's `civil_from_days`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+fn start(kind: ChallengeKind, commands: &mut Commands, seed: &mut WorldSeed) {
+    let challenge = Challenge::today(kind);
+    seed.fixed = Some(challenge.seed);
+    commands.insert_resource(challenge);
+    commands.set_state(AppState::InGame);
+}
+
+pub fn start_daily(
+    _trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut seed: ResMut<WorldSeed>,
+) {
+    start(ChallengeKind::Daily, &mut commands, &mut seed);
+}
+
+pub fn start_weekly(
+    _trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut seed: ResMut<WorldSeed>,
+) {
+    start(ChallengeKind::Weekly, &mut commands, &mut seed);
+}
+
+fn end_challenge(mut commands: Commands, mut seed: ResMut<WorldSeed>) {
+    seed.fixed = None;
+    commands.remove_resource::<Challenge>();
+}
+
+fn back_to_menu(_trigger: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.set_state(AppState::MainMenu);
+}
+
+fn setup_result(
+    mut commands: Commands,
+    challenge: Res<Challenge>,
+    leaderboard: Res<Leaderboard>,
+    latest: Option<Res<LatestEntry>>,
+    loc: Res<Localization>,
+) {
+    let name = loc.tr(&format!("challenge-{}", challenge.kind.id()));
+
+    commands.spawn((Camera2d, StateScoped(GameState::ChallengeResult)));
+    commands
+        .spawn((
+            StateScoped(GameState::ChallengeResult),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(30.0),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            // runs that aren't submitted, like those on an authored map, have no entry
+            let Some(latest) = latest else {
+                parent.spawn((
+                    Text::new(loc.tr_args(
+                        "challenge-unranked",
+                        &[
+                            ("challenge", name.into()),
+                            ("date", challenge.date().into()),
+                        ],
+                    )),
+                    TextLayout::new_with_justify(JustifyText::Center),
+                ));
+                parent.spawn(menu_button(&loc)).observe(back_to_menu);
+                return;
+            };
+            let result = &latest.0;
+            let scores = leaderboard.board(result);
+            let rank = leaderboard.rank(result);
+            let code = challenge.share_code(result.score);
+            info!("challenge share code: {code}");
+
+            let title = loc.tr_args(
+                "challenge-result",
+                &[
+                    ("challenge", name.into()),
+                    ("date", challenge.date().into()),
+                    ("score", result.score.into()),
+                    ("rank", rank.into()),
+                    ("runs", scores.len().into()),
+                ],
+            );
+            parent.spawn((
+                Text::new(title),
+                TextLayout::new_with_justify(JustifyText::Center),
+            ));
+            parent.spawn(Text::new(entry_lines(
+                &scores[..scores.len().min(LEADERBOARD_SHOWN)],
+                &loc,
+            )));
+            parent.spawn((
+                Text::new(loc.tr_args("challenge-share-code", &[("code", code.into())])),
+                TextLayout::new_with_justify(JustifyText::Center),
+            ));
+            parent.spawn(menu_button(&loc)).observe(back_to_menu);
+        });
+}
+
+fn menu_button(loc: &Localization) -> impl Bundle {
+    (
+        Button,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(15.0),
+            right: Val::Px(15.0),
+            width: Val::Px(150.0),
+            height: Val::Px(65.0),
+            border: UiRect::all(Val::Px(1.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BorderColor(Color::WHITE),
+        BorderRadius::all(Val::Px(5.0)),
+        children![(Text::new(loc.tr("challenge-menu")),)],
+    )
+}
+
+impl Plugin for ChallengePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(AppState::InGame), end_challenge)
+            .add_systems(OnEnter(GameState::ChallengeResult), setup_result);
+    }
+}
@@ -12,7 +12,14 @@ use crate::{
     materials::{link_material::LinkMaterial, lumina_material::LuminaMaterial},
 };
 
-use super::{scaling::Scaling, ship::Ship};
+use super::{
+    game_loop::Simulation,
+    map::{AuthoredMap, LuminaKind, Map},
+    palette::Palette,
+    scaling::Scaling,
+    settings::Settings,
+    ship::Ship,
+};
 
 #[derive(Asset, TypePath, AsBindGroup, Debug, Clone, Default)]
 struct StarfieldMaterial {
@@ -30,7 +37,7 @@ impl Material2d for StarfieldMaterial {
 }
 
 #[derive(Resource, Default)]
-struct ChunkResources {
+pub struct ChunkResources {
     material: Handle<StarfieldMaterial>,
     mesh: Handle<Mesh>,
     resource_mesh: Handle<Mesh>,
@@ -40,8 +47,42 @@ struct ChunkResources {
     link_material: Handle<LinkMaterial>,
 }
 
+/// Seed the world of the current run is generated from. Each chunk derives its
+/// own generator from it, so a seed always produces the same world.
 #[derive(Resource, Default)]
-struct Chunks {
+pub struct WorldSeed {
+    /// Used for every run instead of a random seed while set.
+    pub fixed: Option<u64>,
+    current: u64,
+}
+
+impl WorldSeed {
+    pub fn current(&self) -> u64 {
+        self.current
+    }
+
+    fn chunk_rng(&self, chunk: IVec2) -> StdRng {
+        let mut seed = self.current;
+        for value in [chunk.x, chunk.y] {
+            seed = (seed ^ value as u32 as u64).wrapping_mul(0x100000001b3);
+        }
+        StdRng::seed_from_u64(seed)
+    }
+}
+
+/// Generator for the random events of the current run. It is seeded from the
+/// world seed, so a run can be simulated again.
+#[derive(Resource, Deref, DerefMut)]
+pub struct RunRng(StdRng);
+
+impl RunRng {
+    pub fn new(seed: u64) -> Self {
+        RunRng(StdRng::seed_from_u64(seed))
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Chunks {
     created: HashMap<IVec2, Entity>,
 }
 
@@ -53,7 +94,8 @@ pub struct Attached {
 
 #[derive(Component, Default)]
 pub struct Lumina {
-    pub targets: HashSet<Entity>,
+    /// Linked Lumina, in the order the links were made.
+    pub targets: Vec<Entity>,
 }
 
 #[derive(Component)]
@@ -88,12 +130,20 @@ struct Contains(Vec<Entity>);
 #[derive(Component, Default)]
 struct Nearby;
 
+/// Sent when the ship moves its attachment from one Lumina to another.
 #[derive(Event)]
-struct AttachedChangeEvent {
+pub struct AttachedChangeEvent {
     from: Entity,
     to: Entity,
 }
 
+/// Sent whenever the ship forges a new link between two Lumina.
+#[derive(Event)]
+pub struct LinkCreatedEvent {
+    pub from: Entity,
+    pub to: Entity,
+}
+
 #[derive(Component)]
 struct AttachmentLine;
 
@@ -109,6 +159,8 @@ fn setup(
     mut starfield_materials: ResMut<Assets<StarfieldMaterial>>,
     mut link_materials: ResMut<Assets<LinkMaterial>>,
     mut lumina_materials: ResMut<Assets<LuminaMaterial>>,
+    palette: Res<Palette>,
+    settings: Res<Settings>,
 ) {
     commands.insert_resource(ChunkResources {
         material: starfield_materials.add(StarfieldMaterial::default()),
@@ -119,24 +171,27 @@ fn setup(
             },
         }),
         lumina_material: lumina_materials.add(LuminaMaterial {
-            base_color: LinearRgba::rgb(0.0, 0.3, 0.8),
+            base_color: palette.lumina,
             fill_color: LinearRgba::rgb(0.0, 0.0, 0.0),
-            bloom: 75.0,
+            bloom: settings.emissive(75.0),
             freq: 2.0,
+            pattern: 0.0,
         }),
         lumina_cooldown_material: lumina_materials.add(LuminaMaterial {
-            base_color: LinearRgba::rgb(0.0, 0.15, 0.4),
+            base_color: palette.lumina_cooldown,
             fill_color: LinearRgba::rgb(0.0, 0.0, 0.0),
             bloom: 1.0,
             freq: 2.0,
+            pattern: 1.0,
         }),
         resource_mesh: meshes.add(Circle::new(40.0)).into(),
         line_mesh: meshes.add(Mesh::from(Rectangle::default())),
         link_material: link_materials.add(LinkMaterial {
-            base_color: LinearRgba::rgb(0.0, 0.3, 0.8),
+            base_color: palette.link,
             bloom: 5.0,
             noise_freq: 0.02,
             noise_speed: 2.0,
+            dashes: 0.0,
         }),
     });
 }
@@ -164,6 +219,7 @@ fn update_attachment_line(
         (With<AttachmentLine>, Without<Lumina>, Without<Ship>),
     >,
     mut link_materials: ResMut<Assets<LinkMaterial>>,
+    palette: Res<Palette>,
 ) {
     let end = ship_transform.translation.xy();
     let mut visibility = Visibility::Hidden;
@@ -175,11 +231,13 @@ fn update_attachment_line(
                 visibility = Visibility::Visible;
                 let link_material = link_materials.get_mut(&line.2.0).unwrap();
                 if attached.in_range {
-                    link_material.base_color = LinearRgba::rgb(0.0, 0.15, 0.4);
+                    link_material.base_color = palette.attach_in_range;
                     link_material.bloom = 5.0;
+                    link_material.dashes = 0.0;
                 } else {
-                    link_material.base_color = LinearRgba::rgb(0.1, 0.1, 0.1);
+                    link_material.base_color = palette.attach_out_of_range;
                     link_material.bloom = 1.0;
+                    link_material.dashes = 1.0;
                 };
             }
         }
@@ -263,75 +321,195 @@ fn iter_surrounding_chunks(position: Vec2) -> impl Iterator<Item = IVec2> {
     (-1..=1).flat_map(move |dx| (-1..=1).map(move |dy| chunk_base + IVec2 { x: dx, y: dy }))
 }
 
+/// Spawns a chunk and its procedural Lumina, leaving out those inside the
+/// bounds of an authored map.
+fn populate_chunk(
+    commands: &mut Commands,
+    chunks: &mut Chunks,
+    resources: &ChunkResources,
+    seed: &WorldSeed,
+    map: Option<&Map>,
+    chunk_index: IVec2,
+) -> Entity {
+    if let Some(chunk_entity) = chunks.created.get(&chunk_index) {
+        return *chunk_entity;
+    }
+    let cell_size = CHUNK_SIZE / CELLS_PER_CHUNK as f32;
+    let chunk_position = Vec2 {
+        x: chunk_index.x as f32 * CHUNK_SIZE + CHUNK_SIZE / 2.0,
+        y: chunk_index.y as f32 * CHUNK_SIZE + CHUNK_SIZE / 2.0,
+    };
+    let mut rng = seed.chunk_rng(chunk_index);
+    let chunk_entity = commands
+        .spawn((
+            Chunk,
+            Name::from("Chunk"),
+            StateScoped(GameState::Playing),
+            Mesh2d(resources.mesh.clone()),
+            MeshMaterial2d(resources.material.clone()),
+            Transform::from_xyz(chunk_position.x, chunk_position.y, -10.0),
+        ))
+        .id();
+    for xi in 0..CELLS_PER_CHUNK {
+        for yi in 0..CELLS_PER_CHUNK {
+            let distance = (chunk_index * CELLS_PER_CHUNK
+                + IVec2 {
+                    x: xi as i32,
+                    y: yi as i32,
+                })
+            .as_vec2()
+            .length();
+            let probability = (-RESOURCE_DECAY_RATE * distance).exp() * 0.8 + 0.01;
+            if rng.random_range(0.0..1.0) > probability {
+                continue;
+            }
+            let x_offset = rng.random_range(-0.4..0.4) * cell_size - CHUNK_SIZE / 2.0;
+            let y_offset = rng.random_range(-0.4..0.4) * cell_size - CHUNK_SIZE / 2.0;
+            let position = Vec2 {
+                x: (0.5 + xi as f32) * cell_size + x_offset,
+                y: (0.5 + yi as f32) * cell_size + y_offset,
+            } + chunk_position;
+            if map.is_some_and(|map| map.bounds.contains(position)) {
+                continue;
+            }
+            commands.spawn(lumina_bundle(resources, chunk_entity, position));
+        }
+    }
+    chunks.created.insert(chunk_index, chunk_entity);
+    chunk_entity
+}
+
+fn lumina_bundle(resources: &ChunkResources, chunk_entity: Entity, position: Vec2) -> impl Bundle {
+    (
+        Lumina::default(),
+        Name::from("Lumina"),
+        ContainedBy(chunk_entity),
+        StateScoped(GameState::Playing),
+        Mesh2d(resources.resource_mesh.clone()),
+        MeshMaterial2d(resources.lumina_material.clone()),
+        Transform::from_xyz(position.x, position.y, 1.0),
+    )
+}
+
+/// Spawns an unlinked Lumina at `position`, in addition to the generated ones.
+pub fn spawn_lumina_at(world: &mut World, position: Vec2) {
+    if let Err(err) = world.run_system_cached_with(spawn_lumina, position) {
+        warn!("failed to spawn Lumina: {err}");
+    }
+}
+
+fn spawn_lumina(
+    In(position): In<Vec2>,
+    mut commands: Commands,
+    mut chunks: ResMut<Chunks>,
+    resources: Res<ChunkResources>,
+    seed: Res<WorldSeed>,
+    map: Option<Res<AuthoredMap>>,
+) {
+    let chunk_entity = populate_chunk(
+        &mut commands,
+        &mut chunks,
+        &resources,
+        &seed,
+        map.as_ref().map(|map| &map.0),
+        (position / CHUNK_SIZE).floor().as_ivec2(),
+    );
+    commands.spawn(lumina_bundle(&resources, chunk_entity, position));
+}
+
 fn populate_nearby_chunks(
     mut commands: Commands,
     mut chunks: ResMut<Chunks>,
     resources: Res<ChunkResources>,
     transform: Query<&Transform, With<Ship>>,
+    seed: Res<WorldSeed>,
+    map: Option<Res<AuthoredMap>>,
 ) {
     if let Ok(transform) = transform.single() {
         let position = transform.translation.xy();
         let chunk = (position / CHUNK_SIZE).floor().as_ivec2();
-        let cell_size = CHUNK_SIZE / CELLS_PER_CHUNK as f32;
         for dx in -1..=1 {
             for dy in -1..=1 {
-                let chunk_index = chunk + IVec2 { x: dx, y: dy };
-                if chunks.created.contains_key(&chunk_index) {
-                    continue;
-                }
-                let chunk_position = Vec2 {
-                    x: chunk_index.x as f32 * CHUNK_SIZE + CHUNK_SIZE / 2.0,
-                    y: chunk_index.y as f32 * CHUNK_SIZE + CHUNK_SIZE / 2.0,
-                };
-                let chunk_entity = commands
-                    .spawn((
-                        Chunk,
-                        Name::from("Chunk"),
-                        StateScoped(GameState::Playing),
-                        Mesh2d(resources.mesh.clone()),
-                        MeshMaterial2d(resources.material.clone()),
-                        Transform::from_xyz(chunk_position.x, chunk_position.y, -10.0),
-                    ))
-                    .id();
-                for xi in 0..CELLS_PER_CHUNK {
-                    for yi in 0..CELLS_PER_CHUNK {
-                        let distance = (chunk_index * CELLS_PER_CHUNK
-                            + IVec2 {
-                                x: xi as i32,
-                                y: yi as i32,
-                            })
-                        .as_vec2()
-                        .length();
-                        let probability = (-RESOURCE_DECAY_RATE * distance).exp() * 0.8 + 0.01;
-                        if rand::rng().random_range(0.0..1.0) > probability {
-                            continue;
-                        }
-                        let x_offset =
-                            rand::rng().random_range(-0.4..0.4) * cell_size - CHUNK_SIZE / 2.0;
-                        let y_offset =
-                            rand::rng().random_range(-0.4..0.4) * cell_size - CHUNK_SIZE / 2.0;
-                        let position = Vec2 {
-                            x: (0.5 + xi as f32) * cell_size + x_offset,
-                            y: (0.5 + yi as f32) * cell_size + y_offset,
-                        };
-                        commands.spawn((
-                            Lumina::default(),
-                            Name::from("Lumina"),
-                            ContainedBy(chunk_entity),
-                            StateScoped(GameState::Playing),
-                            Mesh2d(resources.resource_mesh.clone()),
-                            MeshMaterial2d(resources.lumina_material.clone()),
-                            Transform::from_xyz(
-                                position.x + chunk_position.x,
-                                position.y + chunk_position.y,
-                                1.0,
-                            ),
-                        ));
-                    }
-                }
-                chunks.created.insert(chunk_index, chunk_entity);
+                populate_chunk(
+                    &mut commands,
+                    &mut chunks,
+                    &resources,
+                    &seed,
+                    map.as_ref().map(|map| &map.0),
+                    chunk + IVec2 { x: dx, y: dy },
+                );
+            }
+        }
+    }
+}
+
+/// Spawns the Lumina of an authored map up front, so its links can be made
+/// before the ship gets there. Authored links don't count towards the score,
+/// but follow the same rules as the ones the player makes.
+pub fn spawn_authored_lumina(
+    mut commands: Commands,
+    mut chunks: ResMut<Chunks>,
+    mut network: ResMut<LuminaNetwork>,
+    resources: Res<ChunkResources>,
+    seed: Res<WorldSeed>,
+    map: Res<AuthoredMap>,
+    scaling: Res<Scaling>,
+) {
+    let map = &map.0;
+    let entities: Vec<Entity> = map
+        .lumina
+        .iter()
+        .map(|lumina| {
+            let chunk_index = (lumina.position / CHUNK_SIZE).floor().as_ivec2();
+            let chunk_entity = populate_chunk(
+                &mut commands,
+                &mut chunks,
+                &resources,
+                &seed,
+                Some(map),
+                chunk_index,
+            );
+            let mut entity =
+                commands.spawn(lumina_bundle(&resources, chunk_entity, lumina.position));
+            if lumina.kind == LuminaKind::Resting {
+                entity.insert(Cooldown);
             }
+            entity.id()
+        })
+        .collect();
+    let mut targets = vec![Vec::new(); entities.len()];
+    for (from, to) in map.links.iter().copied() {
+        let (Some(&from_entity), Some(&to_entity)) = (entities.get(from), entities.get(to)) else {
+            warn!("authored link {from}-{to} names a missing Lumina, skipped");
+            continue;
+        };
+        if from == to {
+            warn!("authored link {from}-{to} ends where it starts, skipped");
+            continue;
+        }
+        if targets[from].contains(&to_entity) {
+            continue;
+        }
+        if targets[from].len() >= scaling.max_links || targets[to].len() >= scaling.max_links {
+            warn!("authored link {from}-{to} exceeds the link limit, skipped");
+            continue;
+        }
+        if network.set.is_linked(from_entity, to_entity) {
+            warn!("authored link {from}-{to} would close a loop, skipped");
+            continue;
         }
+        targets[from].push(to_entity);
+        targets[to].push(from_entity);
+        network.set.link(from_entity, to_entity);
+        commands.spawn((
+            StateScoped(GameState::Playing),
+            Mesh2d(resources.line_mesh.clone()),
+            MeshMaterial2d(resources.link_material.clone()),
+            transform_for_line(map.lumina[from].position, map.lumina[to].position, 20.0),
+        ));
+    }
+    for (entity, targets) in entities.into_iter().zip(targets) {
+        commands.entity(entity).insert(Lumina { targets });
     }
 }
 
@@ -340,6 +518,7 @@ fn create_links(
     mut attached: EventReader<AttachedChangeEvent>,
     mut lumina: Query<(Entity, &Transform, &mut Lumina)>,
     mut network: ResMut<LuminaNetwork>,
+    mut created: EventWriter<LinkCreatedEvent>,
     scaling: Res<Scaling>,
     resources: Res<ChunkResources>,
 ) {
@@ -357,8 +536,12 @@ fn create_links(
             {
                 network.size += 1;
                 network.set.link(from_entity, to_entity);
-                from_lumina.targets.insert(to_entity);
-                to_lumina.targets.insert(from_entity);
+                created.write(LinkCreatedEvent {
+                    from: from_entity,
+                    to: to_entity,
+                });
+                from_lumina.targets.push(to_entity);
+                to_lumina.targets.push(from_entity);
                 commands.spawn((
                     StateScoped(GameState::Playing),
                     Mesh2d(resources.line_mesh.clone()),
@@ -412,11 +595,33 @@ fn lumina_cooldown_ended(
     }
 }
 
+fn apply_visuals(
+    resources: Res<ChunkResources>,
+    palette: Res<Palette>,
+    settings: Res<Settings>,
+    mut link_materials: ResMut<Assets<LinkMaterial>>,
+    mut lumina_materials: ResMut<Assets<LuminaMaterial>>,
+) {
+    if let Some(material) = lumina_materials.get_mut(&resources.lumina_material) {
+        material.base_color = palette.lumina;
+        material.bloom = settings.emissive(75.0);
+    }
+    if let Some(material) = lumina_materials.get_mut(&resources.lumina_cooldown_material) {
+        material.base_color = palette.lumina_cooldown;
+    }
+    if let Some(material) = link_materials.get_mut(&resources.link_material) {
+        material.base_color = palette.link;
+    }
+}
+
 fn setup_game(
     mut commands: Commands,
     resources: Res<ChunkResources>,
     mut link_materials: ResMut<Assets<LinkMaterial>>,
+    mut seed: ResMut<WorldSeed>,
 ) {
+    seed.current = seed.fixed.unwrap_or_else(|| rand::rng().random());
+    commands.insert_resource(RunRng::new(!seed.current));
     commands.insert_resource(Chunks::default());
     commands.insert_resource(LuminaNetwork::default());
     commands.spawn((
@@ -428,6 +633,7 @@ fn setup_game(
             bloom: 5.0,
             noise_freq: 0.02,
             noise_speed: 2.0,
+            dashes: 0.0,
         })),
         Transform::default(),
         Visibility::Hidden,
@@ -444,7 +650,7 @@ impl Plugin for ChunksPlugin {
             .add_systems(
                 Update,
                 (
-                    update_nearby_lumina,
+                    update_nearby_lumina.in_set(Simulation::Network),
                     update_attachment_line,
                     update_starfield,
                 )
@@ -453,16 +659,32 @@ impl Plugin for ChunksPlugin {
             .add_systems(
                 Update,
                 (
-                    populate_nearby_chunks,
-                    create_links,
+                    populate_nearby_chunks
+                        .in_set(Simulation::Network)
+                        .before(update_nearby_lumina),
+                    create_links
+                        .in_set(Simulation::Network)
+                        .after(update_nearby_lumina),
                     lumina_cooldown_started,
                     lumina_cooldown_ended,
-                    create_links,
                 )
                     .run_if(in_state(GameRunState::Playing)),
             )
-            .add_systems(OnEnter(GameState::Playing), setup_game)
+            .add_systems(
+                Update,
+                apply_visuals.run_if(resource_changed::<Palette>.or(resource_changed::<Settings>)),
+            )
+            .init_resource::<WorldSeed>()
+            .add_systems(
+                OnEnter(GameState::Playing),
+                (
+                    setup_game,
+                    spawn_authored_lumina.run_if(resource_exists::<AuthoredMap>),
+                )
+                    .chain(),
+            )
             .add_systems(Startup, setup)
-            .add_event::<AttachedChangeEvent>();
+            .add_event::<AttachedChangeEvent>()
+            .add_event::<LinkCreatedEvent>();
     }
 }
@@ -0,0 +1,341 @@
+use bevy::{input::common_conditions::input_just_pressed, prelude::*, reflect::Struct};
+use bevy_egui::{EguiContextPass, EguiContexts, EguiGlobalSettings, egui};
+
+use crate::{AppState, GameRunState, GameState};
+
+use super::{
+    chunks::{WorldSeed, spawn_lumina_at},
+    game_loop::GameData,
+    scaling::Scaling,
+    ship::Ship,
+    shop::{UPGRADES, UpgradeLevels, grant},
+    story::{StoryAsset, StoryLog, StoryResources},
+};
+
+/// Developer console, opened with the backquote key. Commands and replies are
+/// English only, like the log.
+pub struct ConsolePlugin;
+
+const USAGE: &[&str] = &[
+    "give credits <amount>",
+    "give upgrade <id>",
+    "set scaling.<field> <value>",
+    "teleport <x> <y>",
+    "spawn lumina [<x> <y>]",
+    "seed [<seed> | random]",
+    "story <count>",
+    "state <name>",
+];
+const STATES: &[&str] = &[
+    "menu", "playing", "story", "shop", "history", "paused", "ending",
+];
+/// Lines of output kept.
+const MAX_OUTPUT: usize = 200;
+
+#[derive(Resource, Default)]
+struct Console {
+    open: bool,
+    input: String,
+    output: Vec<String>,
+    history: Vec<String>,
+    /// Index into `history` while stepping through it with the arrow keys.
+    browsing: Option<usize>,
+}
+
+impl Console {
+    fn print(&mut self, text: &str) {
+        self.output.extend(text.lines().map(String::from));
+        let excess = self.output.len().saturating_sub(MAX_OUTPUT);
+        self.output.drain(..excess);
+    }
+
+    fn step_history(&mut self, back: bool) {
+        let index = match (self.browsing, back) {
+            (None, true) => self.history.len().checked_sub(1),
+            (None, false) => None,
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) => Some(index + 1).filter(|index| *index < self.history.len()),
+        };
+        self.browsing = index;
+        self.input = index.map_or_else(String::new, |index| self.history[index].clone());
+    }
+
+    /// Completes the input as far as all matching commands agree, listing them
+    /// if there is more than one.
+    fn complete(&mut self, candidates: &[String]) {
+        let matching: Vec<&String> = candidates
+            .iter()
+            .filter(|candidate| candidate.starts_with(&self.input))
+            .collect();
+        let Some(first) = matching.first() else {
+            return;
+        };
+        let common = matching.iter().fold(first.len(), |common, candidate| {
+            first
+                .chars()
+                .zip(candidate.chars())
+                .take(common)
+                .take_while(|(a, b)| a == b)
+                .count()
+        });
+        self.input = first.chars().take(common).collect();
+        if matching.len() > 1 {
+            let listed: Vec<&str> = matching
+                .iter()
+                .map(|candidate| candidate.trim_end())
+                .collect();
+            self.print(&listed.join("  "));
+        }
+    }
+}
+
+/// Every command the input can be completed to.
+fn candidates() -> Vec<String> {
+    let mut candidates = vec![
+        "help".to_string(),
+        "give credits ".to_string(),
+        "teleport ".to_string(),
+        "spawn lumina".to_string(),
+        "seed ".to_string(),
+        "seed random".to_string(),
+        "story ".to_string(),
+    ];
+    candidates.extend(
+        UPGRADES
+            .iter()
+            .map(|upgrade| format!("give upgrade {}", upgrade.id())),
+    );
+    let scaling = Scaling::default();
+    candidates.extend(
+        (0..scaling.field_len())
+            .filter_map(|index| scaling.name_at(index))
+            .map(|field| format!("set scaling.{field} ")),
+    );
+    candidates.extend(STATES.iter().map(|state| format!("state {state}")));
+    candidates
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("invalid value {value}"))
+}
+
+fn ship_mut(world: &mut World) -> Result<(Mut<'_, Transform>, Mut<'_, Ship>), String> {
+    world
+        .query::<(&mut Transform, &mut Ship)>()
+        .single_mut(world)
+        .map_err(|_| "no run under way".to_string())
+}
+
+fn set_scaling(world: &mut World, name: &str, value: &str) -> Result<String, String> {
+    let mut scaling = world
+        .get_resource_mut::<Scaling>()
+        .ok_or("no game under way")?;
+    let field = scaling
+        .field_mut(name)
+        .ok_or_else(|| format!("unknown field scaling.{name}"))?;
+    if let Some(field) = field.try_downcast_mut::<f32>() {
+        *field = parse(value)?;
+    } else if let Some(field) = field.try_downcast_mut::<usize>() {
+        *field = parse(value)?;
+    } else if let Some(field) = field.try_downcast_mut::<bool>() {
+        *field = parse(value)?;
+    } else {
+        return Err(format!("scaling.{name} can't be set"));
+    }
+    Ok(format!("scaling.{name} = {value}"))
+}
+
+fn set_state(world: &mut World, name: &str) -> Result<String, String> {
+    fn next<S: bevy::state::state::FreelyMutableState>(
+        world: &mut World,
+        state: S,
+    ) -> Result<String, String> {
+        if !world.contains_resource::<State<S>>() {
+            return Err(format!("can't enter {state:?} from here"));
+        }
+        world.resource_mut::<NextState<S>>().set(state.clone());
+        Ok(format!("entering {state:?}"))
+    }
+    match name {
+        "menu" => next(world, AppState::MainMenu),
+        "playing" if !world.contains_resource::<State<GameState>>() => {
+            next(world, AppState::InGame)
+        }
+        "playing" => next(world, GameState::Playing),
+        "story" => next(world, GameState::Story),
+        "shop" => next(world, GameState::Shop),
+        "history" => next(world, GameState::History),
+        "paused" => next(world, GameRunState::Paused),
+        "ending" => next(world, GameRunState::Ending),
+        _ => Err(format!(
+            "unknown state {name}, one of {}",
+            STATES.join(", ")
+        )),
+    }
+}
+
+fn unlock_story(world: &mut World, count: usize) -> Result<String, String> {
+    world.resource_scope(|world, mut log: Mut<StoryLog>| {
+        let handle = &world.resource::<StoryResources>().story;
+        let story = world
+            .resource::<Assets<StoryAsset>>()
+            .get(handle)
+            .ok_or("story not loaded yet")?;
+        let count = count.min(story.entries.len());
+        log.unlock_first(story, count);
+        Ok(format!(
+            "{count} of {} entries unlocked",
+            story.entries.len()
+        ))
+    })
+}
+
+fn execute(world: &mut World, line: &str) -> Result<String, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words.as_slice() {
+        ["help"] => Ok(USAGE.join("\n")),
+        ["give", "credits", amount] => {
+            let amount: u32 = parse(amount)?;
+            let mut data = world.resource_mut::<GameData>();
+            data.network_credits = data.network_credits.saturating_add(amount);
+            Ok(format!("{} credits", data.network_credits))
+        }
+        ["give", "upgrade", id] => {
+            let index = UPGRADES
+                .iter()
+                .position(|upgrade| upgrade.id() == *id)
+                .ok_or_else(|| format!("unknown upgrade {id}"))?;
+            let level = world
+                .try_resource_scope(|world, mut scaling: Mut<Scaling>| {
+                    let mut levels = world.get_resource_mut::<UpgradeLevels>()?;
+                    Some(grant(index, &mut scaling, &mut levels))
+                })
+                .flatten()
+                .ok_or("no game under way")?;
+            Ok(format!("{id} at level {level}"))
+        }
+        ["set", path, value] => match path.strip_prefix("scaling.") {
+            Some(field) => set_scaling(world, field, value),
+            None => Err(format!("can't set {path}")),
+        },
+        ["teleport", x, y] => {
+            let position = Vec2::new(parse(x)?, parse(y)?);
+            let (mut transform, mut ship) = ship_mut(world)?;
+            transform.translation = position.extend(transform.translation.z);
+            ship.linear = Vec2::ZERO;
+            Ok(format!("ship at {position}"))
+        }
+        ["spawn", "lumina", rest @ ..] => {
+            let position = match rest {
+                [] => ship_mut(world)?.0.translation.xy(),
+                [x, y] => {
+                    // only during a run
+                    ship_mut(world)?;
+                    Vec2::new(parse(x)?, parse(y)?)
+                }
+                _ => return Err("usage: spawn lumina [<x> <y>]".to_string()),
+            };
+            spawn_lumina_at(world, position);
+            Ok(format!("Lumina at {position}"))
+        }
+        ["seed"] => {
+            let seed = world.resource::<WorldSeed>();
+            Ok(match seed.fixed {
+                Some(fixed) => format!("seed {}, fixed to {fixed}", seed.current()),
+                None => format!("seed {}", seed.current()),
+            })
+        }
+        ["seed", "random"] => {
+            world.resource_mut::<WorldSeed>().fixed = None;
+            Ok("random seeds from the next run on".to_string())
+        }
+        ["seed", seed] => {
+            let seed = parse(seed)?;
+            world.resource_mut::<WorldSeed>().fixed = Some(seed);
+            Ok(format!("seed {seed} from the next run on"))
+        }
+        ["story", count] => unlock_story(world, parse(count)?),
+        ["state", name] => set_state(world, name),
+        [] => Ok(String::new()),
+        _ => Err(format!("unknown command {line}, try help")),
+    }
+}
+
+/// Lets the game ignore keys and clicks meant for the console or inspector.
+fn absorb_input(mut settings: ResMut<EguiGlobalSettings>) {
+    settings.enable_absorb_bevy_input_system = true;
+}
+
+fn open_console(mut console: ResMut<Console>) {
+    console.open = true;
+}
+
+fn draw_console(mut commands: Commands, mut contexts: EguiContexts, mut console: ResMut<Console>) {
+    if !console.open {
+        return;
+    }
+    let mut submitted = None;
+    egui::Window::new("Console")
+        .default_width(600.0)
+        .show(contexts.ctx_mut(), |ui| {
+            if ui.input_mut(|input| {
+                input.consume_key(egui::Modifiers::NONE, egui::Key::Escape)
+                    || input.consume_key(egui::Modifiers::NONE, egui::Key::Backtick)
+            }) {
+                console.open = false;
+            }
+            if ui.input_mut(|input| input.consume_key(egui::Modifiers::NONE, egui::Key::Tab)) {
+                console.complete(&candidates());
+            }
+            for (key, back) in [(egui::Key::ArrowUp, true), (egui::Key::ArrowDown, false)] {
+                if ui.input_mut(|input| input.consume_key(egui::Modifiers::NONE, key)) {
+                    console.step_history(back);
+                }
+            }
+
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for line in &console.output {
+                        ui.monospace(line);
+                    }
+                });
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut console.input)
+                    .font(egui::TextStyle::Monospace)
+                    .desired_width(f32::INFINITY),
+            );
+            // the key opening the console is typed as well
+            console.input.retain(|c| c != '`');
+            if response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter)) {
+                submitted = Some(std::mem::take(&mut console.input));
+            }
+            response.request_focus();
+        });
+
+    let Some(line) = submitted else {
+        return;
+    };
+    console.browsing = None;
+    console.print(&format!("> {line}"));
+    if !line.trim().is_empty() && console.history.last() != Some(&line) {
+        console.history.push(line.clone());
+    }
+    commands.queue(move |world: &mut World| {
+        let reply = execute(world, &line).unwrap_or_else(|err| err);
+        world.resource_mut::<Console>().print(&reply);
+    });
+}
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Console>()
+            .add_systems(Startup, absorb_input)
+            .add_systems(
+                Update,
+                open_console.run_if(input_just_pressed(KeyCode::Backquote)),
+            )
+            .add_systems(EguiContextPass, draw_console);
+    }
+}
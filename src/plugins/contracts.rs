@@ -0,0 +1,275 @@
+use bevy::prelude::*;
+
+use crate::{AppState, GameRunState, GameState};
+
+use super::{
+    chunks::{LinkCreatedEvent, Lumina},
+    game_loop::{RunStats, Simulation, check_run},
+    localization::Localization,
+};
+
+pub struct ContractsPlugin;
+
+/// Optional goal for a single run, accepted in the shop and paid out when the
+/// run ends. The name is the `contract-<id>` message.
+pub trait Contract {
+    fn id(&self) -> &'static str;
+    fn target(&self) -> u32;
+    /// Network credits paid for completing the contract.
+    fn reward(&self) -> u32;
+    fn progress(&self, stats: &RunStats) -> u32;
+}
+
+const FAR_DISTANCE: f32 = 10_000.0;
+
+struct FarLinksContract;
+
+impl Contract for FarLinksContract {
+    fn id(&self) -> &'static str {
+        "far_links"
+    }
+    fn target(&self) -> u32 {
+        5
+    }
+    fn reward(&self) -> u32 {
+        15
+    }
+    fn progress(&self, stats: &RunStats) -> u32 {
+        stats.far_links
+    }
+}
+
+struct EnergyContract;
+
+impl Contract for EnergyContract {
+    fn id(&self) -> &'static str {
+        "energy"
+    }
+    fn target(&self) -> u32 {
+        2000
+    }
+    fn reward(&self) -> u32 {
+        10
+    }
+    fn progress(&self, stats: &RunStats) -> u32 {
+        stats.energy_delivered as u32
+    }
+}
+
+pub const CONTRACTS: &[&dyn Contract] = &[&FarLinksContract, &EnergyContract];
+
+/// Contracts accepted for the next run.
+#[derive(Resource, Default)]
+pub struct ActiveContracts {
+    ids: Vec<&'static str>,
+}
+
+impl ActiveContracts {
+    pub fn is_active(&self, id: &str) -> bool {
+        self.ids.contains(&id)
+    }
+
+    fn active(&self) -> impl Iterator<Item = &&'static dyn Contract> {
+        CONTRACTS
+            .iter()
+            .filter(|contract| self.is_active(contract.id()))
+    }
+
+    /// Credits earned by the completed contracts.
+    pub fn bonus(&self, stats: &RunStats) -> u32 {
+        self.active()
+            .filter(|contract| contract.progress(stats) >= contract.target())
+            .map(|contract| contract.reward())
+            .sum()
+    }
+
+    fn toggle(&mut self, id: &'static str) {
+        if let Some(index) = self.ids.iter().position(|active| *active == id) {
+            self.ids.remove(index);
+        } else {
+            self.ids.push(id);
+        }
+    }
+}
+
+fn setup_game(mut commands: Commands) {
+    commands.insert_resource(ActiveContracts::default());
+}
+
+fn clear_contracts(mut contracts: ResMut<ActiveContracts>) {
+    contracts.ids.clear();
+}
+
+fn track_links(
+    mut created: EventReader<LinkCreatedEvent>,
+    lumina: Query<&Transform, With<Lumina>>,
+    mut stats: ResMut<RunStats>,
+) {
+    for event in created.read() {
+        let far = [event.from, event.to].iter().all(|entity| {
+            lumina
+                .get(*entity)
+                .is_ok_and(|transform| transform.translation.xy().length() > FAR_DISTANCE)
+        });
+        if far {
+            stats.far_links += 1;
+        }
+    }
+}
+
+fn contract_line(contract: &dyn Contract, loc: &Localization) -> String {
+    loc.tr(&format!("contract-{}", contract.id()))
+}
+
+#[derive(Component)]
+struct ContractHud;
+
+fn setup_hud(mut commands: Commands, contracts: Res<ActiveContracts>) {
+    if contracts.ids.is_empty() {
+        return;
+    }
+    commands.spawn((
+        ContractHud,
+        StateScoped(GameState::Playing),
+        Text::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(15.0),
+            left: Val::Px(15.0),
+            ..default()
+        },
+    ));
+}
+
+fn update_hud(
+    mut hud: Single<&mut Text, With<ContractHud>>,
+    contracts: Res<ActiveContracts>,
+    stats: Res<RunStats>,
+    loc: Res<Localization>,
+) {
+    let text = contracts
+        .active()
+        .map(|contract| {
+            let progress = contract.progress(&stats);
+            let key = if progress >= contract.target() {
+                "contract-complete"
+            } else {
+                "contract-progress"
+            };
+            loc.tr_args(
+                key,
+                &[
+                    ("contract", contract_line(*contract, &loc).into()),
+                    ("progress", progress.min(contract.target()).into()),
+                    ("target", contract.target().into()),
+                ],
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if hud.0 != text {
+        hud.0 = text;
+    }
+}
+
+#[derive(Component)]
+struct ContractPanel;
+
+#[derive(Component)]
+struct ContractButton(&'static str);
+
+fn contract_clicked(
+    trigger: Trigger<Pointer<Click>>,
+    button: Query<&ContractButton>,
+    mut contracts: ResMut<ActiveContracts>,
+    mut commands: Commands,
+) {
+    if let Ok(button) = button.get(trigger.target()) {
+        contracts.toggle(button.0);
+        commands.run_system_cached(rebuild_panel);
+    }
+}
+
+fn setup_panel(mut commands: Commands) {
+    commands.spawn((
+        ContractPanel,
+        StateScoped(GameState::Shop),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(15.0),
+            right: Val::Px(15.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(10.0),
+            ..default()
+        },
+    ));
+    commands.run_system_cached(rebuild_panel);
+}
+
+fn rebuild_panel(
+    mut commands: Commands,
+    panel: Single<Entity, With<ContractPanel>>,
+    contracts: Res<ActiveContracts>,
+    loc: Res<Localization>,
+) {
+    commands.entity(*panel).despawn_related::<Children>();
+    commands.entity(*panel).with_children(|parent| {
+        parent.spawn(Text::new(loc.tr("contracts-title")));
+        for contract in CONTRACTS {
+            let color = if contracts.is_active(contract.id()) {
+                Color::WHITE
+            } else {
+                Color::srgb(0.4, 0.4, 0.4)
+            };
+            parent
+                .spawn((
+                    Button,
+                    ContractButton(contract.id()),
+                    Node {
+                        width: Val::Px(300.0),
+                        border: UiRect::all(Val::Px(1.0)),
+                        padding: UiRect::all(Val::Px(10.0)),
+                        ..default()
+                    },
+                    BorderColor(color),
+                    BorderRadius::all(Val::Px(5.0)),
+                    children![(
+                        TextColor(color),
+                        TextFont {
+                            font_size: 14.0,
+                            ..default()
+                        },
+                        Text::new(loc.tr_args(
+                            "contract-offer",
+                            &[
+                                ("contract", contract_line(*contract, &loc).into()),
+                                ("reward", contract.reward().into()),
+                            ],
+                        )),
+                    )],
+                ))
+                .observe(contract_clicked);
+        }
+    });
+}
+
+impl Plugin for ContractsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::InGame), setup_game)
+            .add_systems(OnEnter(GameState::Playing), setup_hud)
+            .add_systems(OnExit(GameState::Playing), clear_contracts)
+            .add_systems(OnEnter(GameState::Shop), setup_panel)
+            .add_systems(
+                Update,
+                (
+                    track_links.in_set(Simulation::Rules).before(check_run),
+                    update_hud,
+                )
+                    .run_if(in_state(GameRunState::Playing)),
+            );
+    }
+}
@@ -0,0 +1,440 @@
+use bevy::{
+    input::{common_conditions::input_just_pressed, mouse::MouseWheel},
+    prelude::*,
+};
+
+use crate::{AppState, persistence};
+
+use super::{
+    localization::Localization,
+    map::{AuthoredMap, Hazard, LuminaKind, Map, MapBounds, MapFile, MapLumina},
+    palette::Palette,
+};
+
+/// Map editor for designers. Maps are edited as data and drawn with gizmos,
+/// "play from here" loads them into a normal run.
+pub struct EditorPlugin;
+
+const LUMINA_RADIUS: f32 = 40.0;
+const PAN_SPEED: f32 = 1500.0;
+const RADIUS_STEP: f32 = 25.0;
+const MIN_HAZARD_RADIUS: f32 = 25.0;
+/// Margin the map bounds keep around everything placed.
+const BOUNDS_MARGIN: f32 = 500.0;
+const EDITOR_EXPORT: &str = "map.ron";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Tool {
+    Lumina,
+    Hazard,
+    Link,
+}
+
+impl Tool {
+    fn id(self) -> &'static str {
+        match self {
+            Tool::Lumina => "lumina",
+            Tool::Hazard => "hazard",
+            Tool::Link => "link",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Item {
+    Lumina(usize),
+    Hazard(usize),
+}
+
+#[derive(Resource)]
+struct Editor {
+    map: Map,
+    /// Authored map present before the editor was opened, restored when
+    /// leaving it.
+    launched: Option<Map>,
+    tool: Tool,
+    dragging: Option<Item>,
+    link_from: Option<usize>,
+    camera: Vec2,
+}
+
+/// Present while a run started from the editor is played.
+#[derive(Resource)]
+struct Playtest;
+
+#[derive(Component)]
+struct EditorCamera;
+
+#[derive(Component)]
+struct EditorStatus;
+
+impl Editor {
+    fn item_at(&self, position: Vec2) -> Option<Item> {
+        let lumina = self
+            .map
+            .lumina
+            .iter()
+            .position(|lumina| lumina.position.distance(position) < LUMINA_RADIUS)
+            .map(Item::Lumina);
+        lumina.or_else(|| {
+            self.map
+                .hazards
+                .iter()
+                .position(|hazard| hazard.position.distance(position) < hazard.radius)
+                .map(Item::Hazard)
+        })
+    }
+
+    fn move_item(&mut self, item: Item, position: Vec2) {
+        match item {
+            Item::Lumina(index) => self.map.lumina[index].position = position,
+            Item::Hazard(index) => self.map.hazards[index].position = position,
+        }
+    }
+
+    fn delete(&mut self, item: Item) {
+        match item {
+            Item::Lumina(index) => {
+                self.map.lumina.remove(index);
+                self.map
+                    .links
+                    .retain(|(from, to)| *from != index && *to != index);
+                for (from, to) in self.map.links.iter_mut() {
+                    for end in [from, to] {
+                        if *end > index {
+                            *end -= 1;
+                        }
+                    }
+                }
+                self.link_from = None;
+            }
+            Item::Hazard(index) => {
+                self.map.hazards.remove(index);
+            }
+        }
+    }
+
+    fn toggle_link(&mut self, a: usize, b: usize) {
+        if a == b {
+            return;
+        }
+        let existing = self
+            .map
+            .links
+            .iter()
+            .position(|link| *link == (a, b) || *link == (b, a));
+        match existing {
+            Some(index) => {
+                self.map.links.remove(index);
+            }
+            None => self.map.links.push((a, b)),
+        }
+    }
+
+    /// Grows or shrinks the bounds to everything placed, so procedural
+    /// Lumina only show up away from the authored ones.
+    fn fit_bounds(&mut self) {
+        let points = self
+            .map
+            .lumina
+            .iter()
+            .map(|lumina| (lumina.position, LUMINA_RADIUS))
+            .chain(
+                self.map
+                    .hazards
+                    .iter()
+                    .map(|hazard| (hazard.position, hazard.radius)),
+            )
+            .chain([(self.map.start, 0.0)]);
+        let (min, max) = points.fold(
+            (Vec2::INFINITY, Vec2::NEG_INFINITY),
+            |(min, max), (position, radius)| {
+                (min.min(position - radius), max.max(position + radius))
+            },
+        );
+        self.map.bounds = MapBounds {
+            min: min - BOUNDS_MARGIN,
+            max: max + BOUNDS_MARGIN,
+        };
+    }
+
+    fn save(&mut self, file: Option<&MapFile>) {
+        self.fit_bounds();
+        match file {
+            #[cfg(not(target_arch = "wasm32"))]
+            Some(file) => match self.map.write(&file.0) {
+                Ok(()) => info!("saved map to {}", file.0),
+                Err(err) => warn!("failed to save map to {}: {err}", file.0),
+            },
+            _ => {
+                if let Some(path) = persistence::export(EDITOR_EXPORT, &self.map.to_ron()) {
+                    info!("saved map to {path:?}");
+                }
+            }
+        }
+    }
+}
+
+fn open_editor(mut commands: Commands) {
+    commands.set_state(AppState::Editor);
+}
+
+fn setup_editor(
+    mut commands: Commands,
+    editor: Option<Res<Editor>>,
+    map: Option<Res<AuthoredMap>>,
+) {
+    commands.remove_resource::<Playtest>();
+    let camera = match editor {
+        Some(editor) => editor.camera,
+        None => {
+            let launched = map.map(|map| map.0.clone());
+            let map = launched.clone().unwrap_or_default();
+            let camera = map.start;
+            commands.insert_resource(Editor {
+                map,
+                launched,
+                tool: Tool::Lumina,
+                dragging: None,
+                link_from: None,
+                camera,
+            });
+            camera
+        }
+    };
+    commands.spawn((
+        EditorCamera,
+        StateScoped(AppState::Editor),
+        Camera2d,
+        Transform::from_translation(camera.extend(0.0)),
+    ));
+    commands.spawn((
+        EditorStatus,
+        StateScoped(AppState::Editor),
+        Text::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(15.0),
+            left: Val::Px(15.0),
+            ..default()
+        },
+    ));
+}
+
+fn close_editor(mut commands: Commands, editor: Res<Editor>) {
+    match &editor.launched {
+        Some(map) => commands.insert_resource(AuthoredMap(map.clone())),
+        None => commands.remove_resource::<AuthoredMap>(),
+    }
+    commands.remove_resource::<Editor>();
+    commands.set_state(AppState::MainMenu);
+}
+
+fn play_from_here(mut commands: Commands, mut editor: ResMut<Editor>) {
+    editor.map.start = editor.camera;
+    editor.fit_bounds();
+    commands.insert_resource(AuthoredMap(editor.map.clone()));
+    commands.insert_resource(Playtest);
+    commands.set_state(AppState::InGame);
+}
+
+fn pan_camera(
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut editor: ResMut<Editor>,
+    mut camera: Single<&mut Transform, With<EditorCamera>>,
+) {
+    // Ctrl+S saves rather than moving down
+    if keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    let mut direction = Vec2::ZERO;
+    for (keys_for, step) in [
+        ([KeyCode::KeyW, KeyCode::ArrowUp], Vec2::Y),
+        ([KeyCode::KeyS, KeyCode::ArrowDown], Vec2::NEG_Y),
+        ([KeyCode::KeyA, KeyCode::ArrowLeft], Vec2::NEG_X),
+        ([KeyCode::KeyD, KeyCode::ArrowRight], Vec2::X),
+    ] {
+        if keys.any_pressed(keys_for) {
+            direction += step;
+        }
+    }
+    if direction != Vec2::ZERO {
+        editor.camera += direction * PAN_SPEED * time.delta_secs();
+        camera.translation = editor.camera.extend(0.0);
+    }
+}
+
+fn edit_map(
+    mut editor: ResMut<Editor>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut wheel: EventReader<MouseWheel>,
+    camera: Single<(&Camera, &GlobalTransform), With<EditorCamera>>,
+    window: Single<&Window>,
+    file: Option<Res<MapFile>>,
+) {
+    let ctrl = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    if ctrl && keys.just_pressed(KeyCode::KeyS) {
+        editor.save(file.as_deref());
+    }
+    for (key, tool) in [
+        (KeyCode::Digit1, Tool::Lumina),
+        (KeyCode::Digit2, Tool::Hazard),
+        (KeyCode::Digit3, Tool::Link),
+    ] {
+        if keys.just_pressed(key) {
+            editor.tool = tool;
+            editor.link_from = None;
+        }
+    }
+
+    let (camera, camera_transform) = camera.into_inner();
+    let Some(cursor) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok())
+    else {
+        return;
+    };
+    let hovered = editor.item_at(cursor);
+
+    if let Some(item) = editor.dragging {
+        if buttons.pressed(MouseButton::Left) {
+            editor.move_item(item, cursor);
+            return;
+        }
+        editor.dragging = None;
+    }
+    if buttons.just_pressed(MouseButton::Left) {
+        match (editor.tool, hovered) {
+            (Tool::Link, Some(Item::Lumina(index))) => match editor.link_from.take() {
+                Some(from) => editor.toggle_link(from, index),
+                None => editor.link_from = Some(index),
+            },
+            (Tool::Link, _) => editor.link_from = None,
+            (_, Some(item)) => editor.dragging = Some(item),
+            (Tool::Lumina, None) => editor.map.lumina.push(MapLumina {
+                position: cursor,
+                kind: LuminaKind::Normal,
+            }),
+            (Tool::Hazard, None) => editor.map.hazards.push(Hazard {
+                position: cursor,
+                radius: 200.0,
+                drain_per_sec: 50.0,
+            }),
+        }
+    }
+    if (buttons.just_pressed(MouseButton::Right) || keys.just_pressed(KeyCode::Delete))
+        && let Some(item) = hovered
+    {
+        editor.delete(item);
+    }
+    if let Some(Item::Lumina(index)) = hovered
+        && keys.just_pressed(KeyCode::KeyR)
+    {
+        let lumina = &mut editor.map.lumina[index];
+        lumina.kind = match lumina.kind {
+            LuminaKind::Normal => LuminaKind::Resting,
+            LuminaKind::Resting => LuminaKind::Normal,
+        };
+    }
+    let scrolled: f32 = wheel.read().map(|event| event.y.signum()).sum();
+    if let (Some(Item::Hazard(index)), true) = (hovered, scrolled != 0.0) {
+        let hazard = &mut editor.map.hazards[index];
+        hazard.radius = (hazard.radius + scrolled * RADIUS_STEP).max(MIN_HAZARD_RADIUS);
+    }
+}
+
+fn draw_map(editor: Res<Editor>, palette: Res<Palette>, mut gizmos: Gizmos) {
+    let map = &editor.map;
+    let bounds = map.bounds;
+    gizmos.rect_2d(
+        Isometry2d::from_translation((bounds.min + bounds.max) / 2.0),
+        bounds.max - bounds.min,
+        palette.link.with_alpha(0.3),
+    );
+    for (from, to) in &map.links {
+        gizmos.line_2d(
+            map.lumina[*from].position,
+            map.lumina[*to].position,
+            palette.link,
+        );
+    }
+    for (index, lumina) in map.lumina.iter().enumerate() {
+        let color = if editor.link_from == Some(index) {
+            palette.attach_in_range
+        } else if lumina.kind == LuminaKind::Resting {
+            palette.lumina_cooldown
+        } else {
+            palette.lumina
+        };
+        gizmos.circle_2d(
+            Isometry2d::from_translation(lumina.position),
+            LUMINA_RADIUS,
+            color,
+        );
+    }
+    for hazard in &map.hazards {
+        gizmos.circle_2d(
+            Isometry2d::from_translation(hazard.position),
+            hazard.radius,
+            palette.attach_out_of_range,
+        );
+    }
+    gizmos.cross_2d(
+        Isometry2d::from_translation(editor.camera),
+        20.0,
+        palette.attach_in_range,
+    );
+}
+
+fn update_status(
+    editor: Res<Editor>,
+    mut status: Single<&mut Text, With<EditorStatus>>,
+    loc: Res<Localization>,
+) {
+    let text = format!(
+        "{}\n{}",
+        loc.tr_args(
+            "editor-status",
+            &[
+                ("tool", editor.tool.id().into()),
+                ("lumina", editor.map.lumina.len().into()),
+                ("hazards", editor.map.hazards.len().into()),
+                ("links", editor.map.links.len().into()),
+            ],
+        ),
+        loc.tr("editor-help"),
+    );
+    if status.0 != text {
+        status.0 = text;
+    }
+}
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Editor), setup_editor)
+            .add_systems(
+                Update,
+                (
+                    open_editor.run_if(
+                        input_just_pressed(KeyCode::F2).and(
+                            in_state(AppState::MainMenu)
+                                .or(in_state(AppState::InGame).and(resource_exists::<Playtest>)),
+                        ),
+                    ),
+                    (
+                        (pan_camera, edit_map, draw_map, update_status),
+                        play_from_here.run_if(input_just_pressed(KeyCode::KeyP)),
+                        close_editor.run_if(input_just_pressed(KeyCode::Escape)),
+                    )
+                        .chain()
+                        .run_if(in_state(AppState::Editor)),
+                ),
+            );
+    }
+}
@@ -1,13 +1,17 @@
-use std::time::Duration;
+use std::{cmp::Ordering, time::Duration};
 
 use crate::{GameRunState, GameState, materials::lumina_material::LuminaMaterial};
 
 use super::{
-    chunks::{Attached, Cooldown, Lumina},
+    chunks::{Attached, Cooldown, Lumina, RunRng},
+    game_loop::{RunStats, Simulation},
+    mixer::{PlaySfx, Sfx},
+    palette::Palette,
     scaling::Scaling,
+    settings::Settings,
     ship::{Ship, ShipSprite},
 };
-use bevy::{audio::Volume, prelude::*};
+use bevy::prelude::*;
 use bevy_tweening::{Animator, Tween, lens::SpriteColorLens};
 use rand::Rng;
 
@@ -15,29 +19,101 @@ use rand::Rng;
 struct EnergyResources {
     mesh: Handle<Mesh>,
     material: Handle<LuminaMaterial>,
-    generate_sound: Handle<AudioSource>,
-    reflect_sound: Handle<AudioSource>,
-    charge_sound: Handle<AudioSource>,
-    propagate_sound: Handle<AudioSource>,
+}
+
+/// Tracks charge feedback on the ship sprite. Flashes are kept to fewer than
+/// three per second, in safe mode they are replaced by a glow that fades in and out.
+#[derive(Resource)]
+struct ChargeGlow {
+    last_charge: f32,
+    last_flash: f32,
+    brightness: f32,
+}
+
+impl Default for ChargeGlow {
+    fn default() -> Self {
+        Self {
+            last_charge: f32::NEG_INFINITY,
+            last_flash: f32::NEG_INFINITY,
+            brightness: 0.0,
+        }
+    }
+}
+
+// a little over a third of a second, to stay clear of three flashes a second
+const MIN_FLASH_INTERVAL: f32 = 0.4;
+const GLOW_HOLD_SECS: f32 = 0.5;
+const GLOW_PER_SEC: f32 = 2.0;
+const SAFE_GLOW: f32 = 2.0;
+
+/// Number of pulses spawned this run, used to number them.
+#[derive(Resource, Default)]
+struct EnergyCount(u64);
+
+impl EnergyCount {
+    fn next(&mut self) -> u64 {
+        self.0 += 1;
+        self.0
+    }
+}
+
+/// Sent whenever a pulse of energy finishes travelling along a link.
+#[derive(Event)]
+pub struct PulseEvent {
+    pub from: Entity,
+    pub to: Entity,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Branch {
+    Reflected,
+    Propagated,
+    Terminated,
+}
+
+/// Sent for every way a pulse reaching a Lumina could continue, telling
+/// whether it did.
+#[derive(Event)]
+pub struct BranchEvent {
+    pub at: Entity,
+    pub branch: Branch,
 }
 
 #[derive(Component)]
-struct Energy {
-    target: Entity,
+pub struct Energy {
+    /// Pulses are processed in spawn order, so the result doesn't depend on
+    /// entity ids or storage order.
+    serial: u64,
+    pub target: Entity,
     t: f32,
-    path: Vec<Entity>,
-    returning: bool,
-    distance: f32,
+    /// Lumina passed so far, the last one is where the pulse is coming from.
+    pub path: Vec<Entity>,
+    pub returning: bool,
+    pub distance: f32,
+}
+
+fn by_position(a: &Transform, b: &Transform) -> Ordering {
+    a.translation
+        .x
+        .total_cmp(&b.translation.x)
+        .then(a.translation.y.total_cmp(&b.translation.y))
+}
+
+fn setup_run(mut commands: Commands) {
+    commands.insert_resource(EnergyCount::default());
 }
 
 fn resume_lumina(
     mut commands: Commands,
     attached: Option<Single<&Attached>>,
-    cooldown: Query<Entity, With<Cooldown>>,
+    cooldown: Query<(Entity, &Transform), With<Cooldown>>,
     time: Res<Time>,
     scaling: Res<Scaling>,
+    mut rng: ResMut<RunRng>,
 ) {
-    for entity in cooldown.iter() {
+    let mut cooldown: Vec<_> = cooldown.iter().collect();
+    cooldown.sort_by(|a, b| by_position(a.1, b.1));
+    for (entity, _) in cooldown {
         if attached
             .as_ref()
             .map_or(false, |attached| attached.lumina == entity)
@@ -45,7 +121,7 @@ fn resume_lumina(
             // don't end cooldown while attached
             continue;
         }
-        if rand::rng().random_range(0.0..1.0) < scaling.lumina_resume_per_sec * time.delta_secs() {
+        if rng.random_range(0.0..1.0) < scaling.lumina_resume_per_sec * time.delta_secs() {
             commands.entity(entity).remove::<Cooldown>();
         }
     }
@@ -59,6 +135,9 @@ fn generate_energy(
     cooldown: Query<&Cooldown>,
     resources: Res<EnergyResources>,
     scaling: Res<Scaling>,
+    mut sfx: EventWriter<PlaySfx>,
+    mut rng: ResMut<RunRng>,
+    mut count: ResMut<EnergyCount>,
 ) {
     if let Some(ref attached) = attached {
         if !attached.in_range {
@@ -70,18 +149,20 @@ fn generate_energy(
         }
         let mut generated = false;
         for target in lumina.targets.iter() {
-            if rand::rng().random_range(0.0..1.0) > scaling.generation_per_sec * time.delta_secs() {
+            if rng.random_range(0.0..1.0) > scaling.generation_per_sec * time.delta_secs() {
                 continue;
             }
             if !generated {
-                commands.spawn((
-                    AudioPlayer::new(resources.generate_sound.clone()),
-                    PlaybackSettings::DESPAWN.with_volume(Volume::Linear(0.5)),
-                ));
+                sfx.write(PlaySfx {
+                    sfx: Sfx::Generate,
+                    position: Some(transform.translation.xy()),
+                    volume: 0.5,
+                });
             }
             generated = true;
             commands.spawn((
                 Energy {
+                    serial: count.next(),
                     target: *target,
                     t: 0.0,
                     path: vec![attached.lumina],
@@ -94,7 +175,7 @@ fn generate_energy(
                 MeshMaterial2d(resources.material.clone()),
                 transform.clone(),
             ));
-            if rand::rng().random_range(0.0..1.0) < scaling.lumina_cooldown_per_generation {
+            if rng.random_range(0.0..1.0) < scaling.lumina_cooldown_per_generation {
                 commands.entity(attached.lumina).insert(Cooldown);
                 return;
             }
@@ -111,10 +192,14 @@ fn move_energy(
     lumina: Query<(&Transform, &Lumina)>,
     resources: Res<EnergyResources>,
     scaling: Res<Scaling>,
-    ship: Single<&Transform, With<Ship>>,
+    mut sfx: EventWriter<PlaySfx>,
+    mut pulses: EventWriter<PulseEvent>,
+    mut branches: EventWriter<BranchEvent>,
+    mut rng: ResMut<RunRng>,
+    mut count: ResMut<EnergyCount>,
 ) {
-    let mut reflected: f32 = f32::INFINITY;
-    let mut propagated: f32 = f32::INFINITY;
+    let mut energy: Vec<_> = energy.into_iter().collect();
+    energy.sort_by_key(|(_, _, energy)| energy.serial);
     for (entity, mut transform, mut energy) in energy {
         if energy.path.is_empty() {
             continue;
@@ -134,15 +219,20 @@ fn move_energy(
         energy.t = new_t;
         if energy.t >= 1.0 {
             energy.distance += total_distance;
+            pulses.write(PulseEvent { from, to });
             if energy.returning {
                 // Continue returning
                 energy.path.pop();
                 if energy.path.len() >= 1 {
                     energy.target = energy.path.pop().unwrap();
                     energy.path.push(to);
-                    if rand::rng().random_range(0.0..1.0) > scaling.propagation_probability {
+                    let branch = if rng.random_range(0.0..1.0) > scaling.propagation_probability {
                         energy.path.clear();
-                    }
+                        Branch::Terminated
+                    } else {
+                        Branch::Propagated
+                    };
+                    branches.write(BranchEvent { at: to, branch });
                     energy.t = 0.0;
                 }
             } else {
@@ -150,24 +240,41 @@ fn move_energy(
                 let mut all_terminated = true;
                 for target in to_lumina.targets.iter() {
                     let terminated = if *target == from {
-                        rand::rng().random_range(0.0..1.0) > scaling.reflection_probability
+                        rng.random_range(0.0..1.0) > scaling.reflection_probability
                     } else {
-                        rand::rng().random_range(0.0..1.0) > scaling.propagation_probability
+                        rng.random_range(0.0..1.0) > scaling.propagation_probability
                     };
                     if terminated {
+                        branches.write(BranchEvent {
+                            at: to,
+                            branch: Branch::Terminated,
+                        });
                         continue;
                     } else {
                         all_terminated = false;
                     }
-                    if *target == from {
-                        reflected = reflected.min(to_pos.distance(ship.translation.xy()));
-                    } else {
-                        propagated = propagated.min(to_pos.distance(ship.translation.xy()));
-                    }
+                    branches.write(BranchEvent {
+                        at: to,
+                        branch: if *target == from {
+                            Branch::Reflected
+                        } else {
+                            Branch::Propagated
+                        },
+                    });
+                    sfx.write(PlaySfx {
+                        sfx: if *target == from {
+                            Sfx::Reflect
+                        } else {
+                            Sfx::Propagate
+                        },
+                        position: Some(to_pos),
+                        volume: 0.5,
+                    });
                     let mut path = energy.path.clone();
                     path.push(to);
                     commands.spawn((
                         Energy {
+                            serial: count.next(),
                             target: *target,
                             t: 0.0,
                             path,
@@ -189,38 +296,32 @@ fn move_energy(
             }
         }
     }
-
-    if reflected < 1000.0 {
-        commands.spawn((
-            AudioPlayer::new(resources.reflect_sound.clone()),
-            PlaybackSettings::DESPAWN
-                .with_volume(Volume::Linear(0.5 * ((1000.0 - reflected) / 1000.0))),
-        ));
-    } else if propagated < 1000.0 {
-        commands.spawn((
-            AudioPlayer::new(resources.propagate_sound.clone()),
-            PlaybackSettings::DESPAWN
-                .with_volume(Volume::Linear(0.5 * ((1000.0 - propagated) / 1000.0))),
-        ));
-    }
 }
 
 fn deliver_energy(
     mut commands: Commands,
     mut ship: Single<&mut Ship>,
     attached: Option<Single<&Attached>>,
-    energy: Query<(Entity, &mut Energy)>,
+    energy: Query<(Entity, &Energy)>,
     scaling: Res<Scaling>,
     ship_sprite: Single<Entity, With<ShipSprite>>,
-    resources: Res<EnergyResources>,
+    settings: Res<Settings>,
+    mut sfx: EventWriter<PlaySfx>,
+    time: Res<Time>,
+    mut glow: ResMut<ChargeGlow>,
+    mut stats: ResMut<RunStats>,
 ) {
     let mut animate = false;
+    let mut delivered = 0.0;
+    let mut energy: Vec<_> = energy.iter().collect();
+    energy.sort_by_key(|(_, energy)| energy.serial);
     for (entity, energy) in energy {
         if attached.as_ref().map_or(false, |attached| {
             attached.in_range && energy.target == attached.lumina
         }) && energy.path.is_empty()
         {
             ship.energy += energy.distance * scaling.energy_extraction;
+            delivered += energy.distance * scaling.energy_extraction;
             animate = true;
             commands.entity(entity).despawn();
         } else if energy.path.is_empty() {
@@ -233,16 +334,29 @@ fn deliver_energy(
                     || (energy.path.len() > 1 && energy.t == 0.0))
         }) {
             ship.energy += energy.distance * scaling.energy_extraction;
+            delivered += energy.distance * scaling.energy_extraction;
             animate = true;
             commands.entity(entity).despawn();
         }
     }
+    // energy arriving at a full battery still counts as delivered
+    stats.energy_delivered += delivered;
     ship.energy = ship.energy.min(scaling.max_battery + scaling.max_capacitor);
     if animate {
-        commands.spawn((
-            AudioPlayer::new(resources.charge_sound.clone()),
-            PlaybackSettings::DESPAWN.with_volume(Volume::Linear(0.15)),
-        ));
+        sfx.write(PlaySfx {
+            sfx: Sfx::Charge,
+            position: None,
+            volume: 0.15,
+        });
+        let now = time.elapsed_secs();
+        glow.last_charge = now;
+        if settings.reduced_motion
+            || settings.safe_mode
+            || now - glow.last_flash < MIN_FLASH_INTERVAL
+        {
+            return;
+        }
+        glow.last_flash = now;
         commands.entity(*ship_sprite).insert(Animator::new(
             Tween::new(
                 EaseFunction::QuinticIn,
@@ -264,42 +378,386 @@ fn deliver_energy(
     }
 }
 
+fn update_charge_glow(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut glow: ResMut<ChargeGlow>,
+    mut sprite: Single<&mut Sprite, With<ShipSprite>>,
+) {
+    let target = if settings.safe_mode && time.elapsed_secs() - glow.last_charge < GLOW_HOLD_SECS {
+        1.0
+    } else {
+        0.0
+    };
+    if target == 0.0 && glow.brightness == 0.0 {
+        return;
+    }
+    let step = GLOW_PER_SEC * time.delta_secs();
+    glow.brightness += (target - glow.brightness).clamp(-step, step);
+    let value = 1.0 + (SAFE_GLOW - 1.0) * glow.brightness;
+    sprite.color = Color::srgb(value, value, value);
+}
+
 fn setup(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut lumina_materials: ResMut<Assets<LuminaMaterial>>,
-    server: Res<AssetServer>,
+    palette: Res<Palette>,
+    settings: Res<Settings>,
 ) {
     commands.insert_resource(EnergyResources {
         material: lumina_materials.add(LuminaMaterial {
-            base_color: LinearRgba::rgb(0.0, 0.3, 0.8),
-            fill_color: LinearRgba::rgb(0.0, 0.3, 0.8),
-            bloom: 75.0,
+            base_color: palette.energy,
+            fill_color: palette.energy,
+            bloom: settings.emissive(75.0),
             freq: 2.0,
+            pattern: 0.0,
         }),
         mesh: meshes.add(Circle::new(10.0)).into(),
-        charge_sound: server.load("charge.ogg"),
-        generate_sound: server.load("generate.ogg"),
-        propagate_sound: server.load("propagate.ogg"),
-        reflect_sound: server.load("reflect.ogg"),
     });
 }
 
+fn apply_visuals(
+    resources: Res<EnergyResources>,
+    palette: Res<Palette>,
+    settings: Res<Settings>,
+    mut lumina_materials: ResMut<Assets<LuminaMaterial>>,
+) {
+    if let Some(material) = lumina_materials.get_mut(&resources.material) {
+        material.base_color = palette.energy;
+        material.fill_color = palette.energy;
+        material.bloom = settings.emissive(75.0);
+    }
+}
+
 pub struct EnergyPlugin;
 
 impl Plugin for EnergyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup)
+        app.init_resource::<ChargeGlow>()
+            .add_event::<PulseEvent>()
+            .add_event::<BranchEvent>()
+            .add_systems(Startup, setup)
+            .add_systems(OnEnter(GameState::Playing), setup_run)
             .add_systems(
                 Update,
-                (generate_energy, deliver_energy, resume_lumina)
+                (
+                    resume_lumina.before(generate_energy),
+                    generate_energy.before(move_energy),
+                    deliver_energy.after(move_energy),
+                )
+                    .in_set(Simulation::Energy)
                     .run_if(in_state(GameRunState::Playing)),
             )
-            .add_systems(Startup, setup)
             .add_systems(
                 Update,
-                (move_energy)
+                update_charge_glow.run_if(in_state(GameRunState::Playing)),
+            )
+            .add_systems(
+                Update,
+                move_energy
+                    .in_set(Simulation::Energy)
                     .run_if(in_state(GameRunState::Playing).or(in_state(GameRunState::Ending))),
+            )
+            .add_systems(
+                Update,
+                apply_visuals.run_if(resource_changed::<Palette>.or(resource_changed::<Settings>)),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::plugins::chunks::RunRng;
+
+    /// Lumina laid out one per grid cell, so that none overlap.
+    #[derive(Debug, Clone)]
+    struct Network {
+        positions: Vec<Vec2>,
+        links: Vec<(usize, usize)>,
+    }
+
+    fn network() -> impl Strategy<Value = Network> {
+        (2usize..8)
+            .prop_flat_map(|count| {
+                (
+                    prop::collection::vec((0.0f32..200.0, 0.0f32..200.0), count),
+                    prop::collection::vec((0..count, 0..count), 1..count * 2),
+                )
+            })
+            .prop_map(|(offsets, links)| {
+                let count = offsets.len();
+                let positions = offsets
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, (x, y))| {
+                        Vec2::new(
+                            (index % 3) as f32 * 300.0 + x,
+                            (index / 3) as f32 * 300.0 + y,
+                        )
+                    })
+                    .collect();
+                // like in the game, links only join separate networks
+                let mut networks: Vec<usize> = (0..count).collect();
+                let mut forest = Vec::new();
+                for (a, b) in links {
+                    let (joined, into) = (networks[b], networks[a]);
+                    if joined != into {
+                        for network in networks.iter_mut().filter(|network| **network == joined) {
+                            *network = into;
+                        }
+                        forest.push((a, b));
+                    }
+                }
+                Network {
+                    positions,
+                    links: forest,
+                }
+            })
+    }
+
+    struct Sim {
+        world: World,
+        schedule: Schedule,
+        lumina: Vec<Entity>,
+        ship: Entity,
+    }
+
+    impl Sim {
+        fn new(network: &Network, scaling: Scaling, attached: Option<usize>, seed: u64) -> Self {
+            let mut world = World::new();
+            world.insert_resource(Settings {
+                reduced_motion: true,
+                ..default()
+            });
+            world.insert_resource(scaling);
+            world.insert_resource(RunRng::new(seed));
+            world.insert_resource(Time::<()>::default());
+            world.init_resource::<EnergyCount>();
+            world.init_resource::<EnergyResources>();
+            world.init_resource::<ChargeGlow>();
+            world.init_resource::<RunStats>();
+            world.init_resource::<Events<PlaySfx>>();
+            world.init_resource::<Events<PulseEvent>>();
+            world.init_resource::<Events<BranchEvent>>();
+
+            let lumina: Vec<Entity> = network
+                .positions
+                .iter()
+                .map(|position| {
+                    world
+                        .spawn((
+                            Transform::from_translation(position.extend(0.0)),
+                            Lumina::default(),
+                        ))
+                        .id()
+                })
+                .collect();
+            for (a, b) in network.links.iter().copied() {
+                world
+                    .get_mut::<Lumina>(lumina[a])
+                    .unwrap()
+                    .targets
+                    .push(lumina[b]);
+                world
+                    .get_mut::<Lumina>(lumina[b])
+                    .unwrap()
+                    .targets
+                    .push(lumina[a]);
+            }
+            world.spawn(ShipSprite);
+            let ship = world
+                .spawn(Ship {
+                    linear: Vec2::ZERO,
+                    energy: 0.0,
+                })
+                .id();
+            if let Some(index) = attached {
+                world.entity_mut(ship).insert(Attached {
+                    lumina: lumina[index],
+                    in_range: true,
+                });
+            }
+
+            let mut schedule = Schedule::default();
+            schedule.add_systems((move_energy, deliver_energy).chain());
+            Sim {
+                world,
+                schedule,
+                lumina,
+                ship,
+            }
+        }
+
+        /// Sends a pulse from `from` towards `to`, as `generate_energy` does.
+        fn pulse(&mut self, from: usize, to: usize) {
+            let serial = self.world.resource_mut::<EnergyCount>().next();
+            let transform = *self.world.get::<Transform>(self.lumina[from]).unwrap();
+            self.world.spawn((
+                Energy {
+                    serial,
+                    target: self.lumina[to],
+                    t: 0.0,
+                    path: vec![self.lumina[from]],
+                    returning: false,
+                    distance: 0.0,
+                },
+                transform,
+            ));
+        }
+
+        fn step(&mut self, dt: f32) {
+            self.world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(dt));
+            self.schedule.run(&mut self.world);
+        }
+
+        fn pulses(&mut self) -> Vec<(Entity, Vec<Entity>, Entity, bool)> {
+            self.world
+                .query::<(Entity, &Energy)>()
+                .iter(&self.world)
+                .map(|(entity, energy)| {
+                    (entity, energy.path.clone(), energy.target, energy.returning)
+                })
+                .collect()
+        }
+    }
+
+    /// Checks every pulse. `routes` holds the Lumina each returning pulse has
+    /// yet to pass on its way back, the next one last.
+    fn check_pulses(
+        sim: &mut Sim,
+        links: &HashSet<(Entity, Entity)>,
+        routes: &mut HashMap<Entity, Vec<Entity>>,
+    ) -> Result<(), TestCaseError> {
+        let linked = |a: Entity, b: Entity| links.contains(&(a, b)) || links.contains(&(b, a));
+        for (entity, path, target, returning) in sim.pulses() {
+            let Some(last) = path.last().copied() else {
+                return Err(TestCaseError::fail("pulse survived with an empty path"));
+            };
+            let visited: HashSet<_> = path.iter().collect();
+            prop_assert_eq!(visited.len(), path.len(), "path {:?} circles", path);
+            if !returning {
+                for pair in path.windows(2) {
+                    prop_assert!(linked(pair[0], pair[1]), "path {path:?} leaves the links");
+                }
+                prop_assert!(
+                    linked(last, target),
+                    "{last} isn't linked to target {target}"
+                );
+                prop_assert!(!path.contains(&target), "pulse heads back along {path:?}");
+                continue;
+            }
+            // returning pulses first hop in place at the Lumina they were
+            // reflected towards, then head back one Lumina at a time
+            prop_assert!(
+                last == target || linked(last, target),
+                "{last} isn't linked to target {target}"
+            );
+            let route = routes
+                .entry(entity)
+                .or_insert_with(|| path[..path.len() - 1].to_vec());
+            if route.last() != Some(&target) {
+                route.pop();
+            }
+            prop_assert_eq!(
+                route.last(),
+                Some(&target),
+                "returning pulse strays from its path"
             );
+        }
+        Ok(())
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(128))]
+
+        #[test]
+        fn pulses_follow_the_links(
+            network in network(),
+            reflection in 0.0f32..=1.0,
+            propagation in 0.0f32..=1.0,
+            attached in prop::option::of(0usize..8),
+            sources in prop::collection::vec(any::<prop::sample::Index>(), 1..6),
+            dt in 1.0f32 / 120.0..1.0 / 10.0,
+            seed in any::<u64>(),
+        ) {
+            let scaling = Scaling {
+                reflection_probability: reflection,
+                propagation_probability: propagation,
+                ..default()
+            };
+            prop_assume!(!network.links.is_empty());
+            let attached = attached.filter(|index| *index < network.positions.len());
+            let mut sim = Sim::new(&network, scaling, attached, seed);
+            for source in &sources {
+                let (a, b) = *source.get(&network.links);
+                sim.pulse(a, b);
+            }
+            let links: HashSet<_> = network
+                .links
+                .iter()
+                .map(|(a, b)| (sim.lumina[*a], sim.lumina[*b]))
+                .collect();
+            let mut routes = HashMap::new();
+            for _ in 0..300 {
+                sim.step(dt);
+                check_pulses(&mut sim, &links, &mut routes)?;
+            }
+        }
+
+        #[test]
+        fn delivered_energy_matches_distance(
+            segments in prop::collection::vec(50.0f32..500.0, 1..6),
+            extraction in 0.01f32..1.0,
+            battery in 10.0f32..2000.0,
+            dt in 1.0f32 / 120.0..1.0 / 10.0,
+        ) {
+            // a chain where every pulse reflects and propagates, so every
+            // Lumina sends one pulse back to the start
+            let mut positions = vec![Vec2::ZERO];
+            for length in &segments {
+                positions.push(*positions.last().unwrap() + Vec2::new(*length, 0.0));
+            }
+            let network = Network {
+                links: (1..positions.len()).map(|index| (index - 1, index)).collect(),
+                positions,
+            };
+            let scaling = Scaling {
+                reflection_probability: 1.0,
+                propagation_probability: 1.0,
+                energy_extraction: extraction,
+                max_battery: battery,
+                max_capacitor: 0.0,
+                ..default()
+            };
+            let mut sim = Sim::new(&network, scaling, Some(0), 0);
+            sim.pulse(0, 1);
+            for _ in 0..10_000 {
+                if sim.pulses().is_empty() {
+                    break;
+                }
+                sim.step(dt);
+            }
+            prop_assert!(sim.pulses().is_empty());
+
+            let round_trips: f32 = network.positions[1..]
+                .iter()
+                .map(|position| 2.0 * position.x)
+                .sum();
+            let expected = round_trips * extraction;
+            let energy = sim.world.get::<Ship>(sim.ship).unwrap().energy;
+            let charged = expected.min(battery);
+            prop_assert!((energy - charged).abs() <= charged * 1e-4, "{energy} != {charged}");
+            // a full battery doesn't make the delivered energy any less
+            let delivered = sim.world.resource::<RunStats>().energy_delivered;
+            prop_assert!((delivered - expected).abs() <= expected * 1e-4);
+        }
     }
 }
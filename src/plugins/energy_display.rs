@@ -2,7 +2,7 @@ use bevy::{color::palettes::css, prelude::*};
 
 use crate::{GameRunState, GameState};
 
-use super::{scaling::Scaling, ship::Ship};
+use super::{localization::Localization, palette::Palette, scaling::Scaling, ship::Ship};
 
 #[derive(Component)]
 struct EnergyText;
@@ -13,6 +13,13 @@ struct BatteryFill;
 #[derive(Component)]
 struct CapacitorFill;
 
+/// Shown next to the battery bar when it runs low, so the state isn't
+/// conveyed by the bar color alone.
+#[derive(Component)]
+struct LowBatteryMarker;
+
+const LOW_BATTERY: f32 = 0.25;
+
 fn setup_game(mut commands: Commands) {
     commands.spawn((
         StateScoped(GameState::Playing),
@@ -29,7 +36,7 @@ fn setup_game(mut commands: Commands) {
             ..default()
         },
         EnergyText,
-        #[cfg(not(debug_assertions))]
+        #[cfg(not(all(feature = "dev-tools", debug_assertions)))]
         Visibility::Hidden,
     ));
     commands.spawn((
@@ -62,6 +69,21 @@ fn setup_game(mut commands: Commands) {
                     BackgroundColor(css::GREEN.into()),
                 )],
             ),
+            (
+                StateScoped(GameState::Playing),
+                LowBatteryMarker,
+                Text::new("!"),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                Node {
+                    width: Val::Px(20.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                Visibility::Hidden,
+            ),
             (
                 StateScoped(GameState::Playing),
                 Node {
@@ -89,32 +111,48 @@ fn setup_game(mut commands: Commands) {
 fn update_energy_text(
     ship: Single<(&Ship, &Transform)>,
     mut energy: Single<&mut Text, With<EnergyText>>,
+    loc: Res<Localization>,
 ) {
-    energy.0 = format!(
-        "Energy: {:.1}\nDistance: {:.0}",
-        ship.0.energy,
-        ship.1.translation.length()
+    energy.0 = loc.tr_args(
+        "energy-debug",
+        &[
+            ("energy", ship.0.energy.into()),
+            ("distance", ship.1.translation.length().into()),
+        ],
     );
 }
 
 fn update_battery_bar(
     ship: Single<&Ship>,
     bar: Single<(&mut Node, &mut BackgroundColor), With<BatteryFill>>,
+    mut marker: Single<&mut Visibility, With<LowBatteryMarker>>,
     scaling: Res<Scaling>,
+    palette: Res<Palette>,
 ) {
     let fraction = ship.energy / scaling.max_battery;
     let (mut bar, mut background) = bar.into_inner();
     bar.width = Val::Px(300.0 * fraction);
-    background.0 = (css::GREEN.mix(&css::RED, 1.0 - fraction)).into();
+    background.0 = palette
+        .battery_full
+        .mix(&palette.battery_empty, 1.0 - fraction.clamp(0.0, 1.0));
+    let visibility = if fraction < LOW_BATTERY {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    marker.set_if_neq(visibility);
 }
 
 fn update_capacitor_bar(
     ship: Single<&Ship>,
-    mut bar: Single<&mut Node, With<CapacitorFill>>,
+    bar: Single<(&mut Node, &mut BackgroundColor), With<CapacitorFill>>,
     scaling: Res<Scaling>,
+    palette: Res<Palette>,
 ) {
     let fraction = (ship.energy - scaling.max_battery).max(0.0) / scaling.max_capacitor;
+    let (mut bar, mut background) = bar.into_inner();
     bar.width = Val::Px(300.0 * fraction);
+    background.0 = palette.capacitor;
 }
 
 pub struct EnergyDisplayPlugin;
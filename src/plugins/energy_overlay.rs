@@ -0,0 +1,162 @@
+use bevy::{
+    color::palettes::css, input::common_conditions::input_just_pressed,
+    platform::collections::HashMap, prelude::*,
+};
+
+use crate::GameState;
+
+use super::{
+    chunks::Lumina,
+    energy::{Branch, BranchEvent, Energy},
+    game_loop::Simulation,
+    localization::Localization,
+};
+
+/// Draws the path of every pulse and what became of the pulses at each
+/// Lumina, to see why energy didn't come back. Toggled with F3.
+pub struct EnergyOverlayPlugin;
+
+const OUTGOING: Srgba = css::ORANGE;
+const RETURNING: Srgba = css::AQUA;
+const PULSE_RADIUS: f32 = 14.0;
+const PULSE_LABEL_OFFSET: Vec2 = Vec2::new(0.0, 20.0);
+const LUMINA_LABEL_OFFSET: Vec2 = Vec2::new(0.0, -60.0);
+/// Above pulses and Lumina.
+const LABEL_Z: f32 = 5.0;
+
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct EnergyGizmos;
+
+#[derive(Default, Clone, Copy)]
+struct BranchCounts {
+    reflected: u32,
+    propagated: u32,
+    terminated: u32,
+}
+
+/// What became of the pulses at each Lumina this run.
+#[derive(Resource, Default)]
+struct BranchStats(HashMap<Entity, BranchCounts>);
+
+/// Text drawn next to a pulse or Lumina, reused from frame to frame.
+#[derive(Component)]
+struct OverlayLabel;
+
+fn hide_overlay(mut store: ResMut<GizmoConfigStore>) {
+    store.config_mut::<EnergyGizmos>().0.enabled = false;
+}
+
+fn toggle_overlay(mut store: ResMut<GizmoConfigStore>) {
+    let config = store.config_mut::<EnergyGizmos>().0;
+    config.enabled = !config.enabled;
+}
+
+fn reset_stats(mut stats: ResMut<BranchStats>) {
+    stats.0.clear();
+}
+
+fn count_branches(mut branches: EventReader<BranchEvent>, mut stats: ResMut<BranchStats>) {
+    for event in branches.read() {
+        let counts = stats.0.entry(event.at).or_default();
+        match event.branch {
+            Branch::Reflected => counts.reflected += 1,
+            Branch::Propagated => counts.propagated += 1,
+            Branch::Terminated => counts.terminated += 1,
+        }
+    }
+}
+
+fn draw_overlay(
+    mut commands: Commands,
+    energy: Query<(&Transform, &Energy), Without<OverlayLabel>>,
+    lumina: Query<&Transform, (With<Lumina>, Without<OverlayLabel>)>,
+    mut labels: Query<(&mut Text2d, &mut Transform, &mut Visibility), With<OverlayLabel>>,
+    stats: Res<BranchStats>,
+    store: Res<GizmoConfigStore>,
+    loc: Res<Localization>,
+    mut gizmos: Gizmos<EnergyGizmos>,
+) {
+    let mut texts = Vec::new();
+    if store.config::<EnergyGizmos>().0.enabled {
+        for (transform, energy) in &energy {
+            let position = transform.translation.xy();
+            let color = if energy.returning {
+                RETURNING
+            } else {
+                OUTGOING
+            };
+            let path = energy
+                .path
+                .iter()
+                .filter_map(|node| lumina.get(*node).ok())
+                .map(|transform| transform.translation.xy());
+            gizmos.linestrip_2d(path.chain([position]), color);
+            gizmos.circle_2d(Isometry2d::from_translation(position), PULSE_RADIUS, color);
+            texts.push((
+                position + PULSE_LABEL_OFFSET,
+                format!("{:.0}", energy.distance),
+            ));
+        }
+        for (node, counts) in &stats.0 {
+            let Ok(transform) = lumina.get(*node) else {
+                continue;
+            };
+            texts.push((
+                transform.translation.xy() + LUMINA_LABEL_OFFSET,
+                loc.tr_args(
+                    "energy-overlay-counts",
+                    &[
+                        ("reflected", counts.reflected.into()),
+                        ("propagated", counts.propagated.into()),
+                        ("terminated", counts.terminated.into()),
+                    ],
+                ),
+            ));
+        }
+    }
+
+    let mut texts = texts.into_iter();
+    for (mut text, mut transform, mut visibility) in &mut labels {
+        match texts.next() {
+            Some((position, message)) => {
+                if text.0 != message {
+                    text.0 = message;
+                }
+                transform.translation = position.extend(LABEL_Z);
+                *visibility = Visibility::Visible;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+    }
+    for (position, message) in texts {
+        commands.spawn((
+            OverlayLabel,
+            StateScoped(GameState::Playing),
+            Text2d::new(message),
+            TextFont {
+                font_size: 14.0,
+                ..default()
+            },
+            Transform::from_translation(position.extend(LABEL_Z)),
+        ));
+    }
+}
+
+impl Plugin for EnergyOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_gizmo_group::<EnergyGizmos>()
+            .init_resource::<BranchStats>()
+            .add_systems(Startup, hide_overlay)
+            .add_systems(OnEnter(GameState::Playing), reset_stats)
+            .add_systems(
+                Update,
+                (
+                    toggle_overlay.run_if(input_just_pressed(KeyCode::F3)),
+                    (count_branches, draw_overlay)
+                        .chain()
+                        .after(Simulation::Rules)
+                        .run_if(in_state(GameState::Playing)),
+                ),
+            );
+    }
+}
@@ -3,17 +3,42 @@ use std::time::Duration;
 use bevy::{prelude::*, state::state::FreelyMutableState};
 use bevy_tweening::{Animator, Tween, TweenCompleted, lens::UiBackgroundColorLens};
 
-use crate::{GameRunState, GameState};
+use crate::{AppState, GameRunState, GameState};
 
 use super::{
+    challenge::Challenge,
     chunks::LuminaNetwork,
+    contracts::ActiveContracts,
+    map::AuthoredMap,
+    mutators::ActiveMutators,
     scaling::Scaling,
+    settings::Settings,
     ship::{Ship, ShipSprite},
+    tutorial::tutorial_active,
 };
 
 pub struct GameLoopPlugin;
 
-fn setup_run(mut commands: Commands, asset_server: Res<AssetServer>, scaling: Res<Scaling>) {
+/// Gameplay systems run in these steps, in order, so that a run plays out the
+/// same way every time it is fed the same input.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Simulation {
+    Input,
+    Ship,
+    Network,
+    Energy,
+    Rules,
+}
+
+pub fn setup_run(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    scaling: Res<Scaling>,
+    settings: Res<Settings>,
+    map: Option<Res<AuthoredMap>>,
+) {
+    let start = map.map_or(Vec2::ZERO, |map| map.0.start);
+    commands.insert_resource(RunStats::default());
     commands.spawn((
         Name::from("Ship"),
         Ship {
@@ -21,7 +46,7 @@ fn setup_run(mut commands: Commands, asset_server: Res<AssetServer>, scaling: Re
             energy: scaling.max_battery,
         },
         StateScoped(GameState::Playing),
-        Transform::from_xyz(0.0, 0.0, 3.0),
+        Transform::from_translation(start.extend(3.0)),
         Camera2d,
         Projection::Orthographic(OrthographicProjection {
             scale: 1.5,
@@ -33,7 +58,7 @@ fn setup_run(mut commands: Commands, asset_server: Res<AssetServer>, scaling: Re
             ..default()
         },
         bevy::core_pipeline::tonemapping::Tonemapping::TonyMcMapface,
-        bevy::core_pipeline::bloom::Bloom::default(),
+        settings.bloom(),
         bevy::core_pipeline::tonemapping::DebandDither::Enabled,
         children![(
             ShipSprite,
@@ -57,17 +82,47 @@ pub struct GameData {
     pub last_run_network_size: u32,
 }
 
-fn check_run(
+/// Per-run measurements, reset at the start of every run.
+#[derive(Resource, Default)]
+pub struct RunStats {
+    pub duration: f32,
+    pub max_distance: f32,
+    /// Links made with both ends far from the point of entry.
+    pub far_links: u32,
+    pub energy_delivered: f32,
+}
+
+/// Score for a finished run. Links dominate, distance breaks ties.
+pub fn run_score(links: u32, distance: f32) -> u32 {
+    links * 100 + (distance / 100.0) as u32
+}
+
+fn track_run(time: Res<Time>, ship: Single<&Transform, With<Ship>>, mut stats: ResMut<RunStats>) {
+    stats.duration += time.delta_secs();
+    stats.max_distance = stats.max_distance.max(ship.translation.xy().length());
+}
+
+pub fn check_run(
     mut commands: Commands,
     ship: Single<&Ship>,
     network: Res<LuminaNetwork>,
     mut game_data: ResMut<GameData>,
+    mutators: Res<ActiveMutators>,
+    contracts: Res<ActiveContracts>,
+    stats: Res<RunStats>,
+    challenge: Option<Res<Challenge>>,
 ) {
     if ship.energy <= 0.0 {
         commands.set_state(GameRunState::Ending);
+        // challenges are played apart from the campaign
+        if challenge.is_some() {
+            return;
+        }
         game_data.runs += 1;
         game_data.last_run_network_size = network.size;
-        game_data.network_credits += network.size;
+        game_data.network_credits +=
+            (network.size as f32 * mutators.credit_multiplier()).round() as u32;
+        game_data.network_credits += contracts.bonus(&stats);
     }
 }
 
@@ -96,12 +151,18 @@ const FADE_OUT: Fade = Fade(0.0, 1.0, EaseFunction::CubicOut);
 fn fade<S: FreelyMutableState>(
     config: Fade,
     next_state: S,
-) -> impl FnMut(Commands, Option<Single<Entity, With<FadeMarker>>>) {
-    move |mut commands: Commands, current: Option<Single<Entity, With<FadeMarker>>>| {
+) -> impl FnMut(Commands, Option<Single<Entity, With<FadeMarker>>>, Res<Settings>) {
+    move |mut commands: Commands,
+          current: Option<Single<Entity, With<FadeMarker>>>,
+          settings: Res<Settings>| {
         if let Some(entity) = current {
             commands.entity(*entity).despawn();
         }
         let next_state = next_state.clone();
+        if settings.reduced_motion {
+            commands.set_state(next_state);
+            return;
+        }
         commands.set_state(FadeState::Fading);
         commands
             .spawn((
@@ -147,12 +208,35 @@ impl Plugin for GameLoopPlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<FadeState>()
             .init_resource::<GameData>()
+            .configure_sets(
+                Update,
+                (
+                    Simulation::Input,
+                    Simulation::Ship,
+                    Simulation::Network,
+                    Simulation::Energy,
+                    Simulation::Rules,
+                )
+                    .chain(),
+            )
             .add_systems(OnEnter(GameState::Playing), setup_run)
-            .add_systems(Update, check_run.run_if(in_state(GameRunState::Playing)))
+            .add_systems(
+                Update,
+                (track_run, check_run)
+                    .chain()
+                    .in_set(Simulation::Rules)
+                    .run_if(in_state(GameRunState::Playing)),
+            )
             .add_systems(OnEnter(FadeState::Ready), reveal)
             .add_systems(
                 OnEnter(GameRunState::Ending),
-                fade(FADE_OUT, GameState::Story),
+                (
+                    fade(FADE_OUT, GameState::Story)
+                        .run_if(not(resource_exists::<Challenge>).and(not(tutorial_active))),
+                    fade(FADE_OUT, GameState::ChallengeResult).run_if(resource_exists::<Challenge>),
+                    // an abandoned tutorial doesn't unlock the campaign
+                    fade(FADE_OUT, AppState::MainMenu).run_if(tutorial_active),
+                ),
             )
             .add_systems(
                 OnEnter(GameRunState::Playing),
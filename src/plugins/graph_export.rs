@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::{GameRunState, GameState, persistence};
+
+use super::{
+    chunks::{Cooldown, LinkCreatedEvent, Lumina, WorldSeed, spawn_authored_lumina},
+    energy::PulseEvent,
+    game_loop::{RunStats, Simulation, check_run},
+    map::AuthoredMap,
+    replay::Playback,
+};
+
+pub struct GraphExportPlugin;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Lumina,
+    /// Lumina that is resting after generating too much energy.
+    Cooldown,
+}
+
+impl NodeKind {
+    fn id(self) -> &'static str {
+        match self {
+            NodeKind::Lumina => "lumina",
+            NodeKind::Cooldown => "cooldown",
+        }
+    }
+}
+
+struct LinkRecord {
+    from: Entity,
+    to: Entity,
+    created: f32,
+    pulses: u32,
+}
+
+/// Links made in the current run, in the order they were made. The link
+/// entities only draw them and are gone once the run is over.
+#[derive(Resource, Default)]
+pub struct LinkLog {
+    links: Vec<LinkRecord>,
+    by_ends: HashMap<(Entity, Entity), usize>,
+}
+
+fn ends(a: Entity, b: Entity) -> (Entity, Entity) {
+    if a < b { (a, b) } else { (b, a) }
+}
+
+#[derive(Serialize, Debug)]
+pub struct GraphNode {
+    pub id: usize,
+    pub x: f32,
+    pub y: f32,
+    pub kind: NodeKind,
+}
+
+#[derive(Serialize, Debug)]
+pub struct GraphEdge {
+    pub source: usize,
+    pub target: usize,
+    /// Seconds into the run the link was made.
+    pub created: f32,
+    /// Pulses that travelled along the link, in either direction.
+    pub pulses: u32,
+}
+
+/// The network of a run, with nodes numbered in the order they were linked.
+#[derive(Serialize, Debug)]
+pub struct NetworkGraph {
+    pub seed: u64,
+    pub duration: f32,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl NetworkGraph {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("graph is plain data")
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let mut out = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n  \
+             <key id=\"x\" for=\"node\" attr.name=\"x\" attr.type=\"float\"/>\n  \
+             <key id=\"y\" for=\"node\" attr.name=\"y\" attr.type=\"float\"/>\n  \
+             <key id=\"kind\" for=\"node\" attr.name=\"kind\" attr.type=\"string\"/>\n  \
+             <key id=\"created\" for=\"edge\" attr.name=\"created\" attr.type=\"float\"/>\n  \
+             <key id=\"pulses\" for=\"edge\" attr.name=\"pulses\" attr.type=\"int\"/>\n",
+        );
+        out.push_str(&format!(
+            "  <graph id=\"network-{:016x}\" edgedefault=\"undirected\">\n",
+            self.seed
+        ));
+        for node in &self.nodes {
+            out.push_str(&format!(
+                "    <node id=\"n{}\"><data key=\"x\">{}</data><data key=\"y\">{}</data>\
+                 <data key=\"kind\">{}</data></node>\n",
+                node.id,
+                node.x,
+                node.y,
+                node.kind.id()
+            ));
+        }
+        for (index, edge) in self.edges.iter().enumerate() {
+            out.push_str(&format!(
+                "    <edge id=\"e{index}\" source=\"n{}\" target=\"n{}\">\
+                 <data key=\"created\">{}</data><data key=\"pulses\">{}</data></edge>\n",
+                edge.source, edge.target, edge.created, edge.pulses
+            ));
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut out = format!("graph network_{:016x} {{\n", self.seed);
+        for node in &self.nodes {
+            // `!` pins the node for layout engines that honour positions
+            out.push_str(&format!(
+                "  n{} [pos=\"{},{}!\", kind=\"{}\"];\n",
+                node.id,
+                node.x,
+                node.y,
+                node.kind.id()
+            ));
+        }
+        for edge in &self.edges {
+            out.push_str(&format!(
+                "  n{} -- n{} [created={}, pulses={}];\n",
+                edge.source, edge.target, edge.created, edge.pulses
+            ));
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn start_log(mut commands: Commands) {
+    commands.insert_resource(LinkLog::default());
+}
+
+/// Logs the links of an authored map as made at the start of the run.
+fn record_authored_links(lumina: Query<(Entity, &Lumina)>, mut log: ResMut<LinkLog>) {
+    let mut links: Vec<_> = lumina
+        .iter()
+        .flat_map(|(entity, lumina)| lumina.targets.iter().map(move |target| (entity, *target)))
+        .filter(|(from, to)| from < to)
+        .collect();
+    links.sort();
+    for (from, to) in links {
+        let index = log.links.len();
+        log.by_ends.insert((from, to), index);
+        log.links.push(LinkRecord {
+            from,
+            to,
+            created: 0.0,
+            pulses: 0,
+        });
+    }
+}
+
+fn record_links(
+    mut created: EventReader<LinkCreatedEvent>,
+    mut pulses: EventReader<PulseEvent>,
+    stats: Res<RunStats>,
+    mut log: ResMut<LinkLog>,
+) {
+    for event in created.read() {
+        let index = log.links.len();
+        log.by_ends.insert(ends(event.from, event.to), index);
+        log.links.push(LinkRecord {
+            from: event.from,
+            to: event.to,
+            created: stats.duration,
+            pulses: 0,
+        });
+    }
+    for event in pulses.read() {
+        if let Some(index) = log.by_ends.get(&ends(event.from, event.to)).copied() {
+            log.links[index].pulses += 1;
+        }
+    }
+}
+
+fn build_graph(
+    log: &LinkLog,
+    lumina: &Query<(&Transform, Has<Cooldown>), With<Lumina>>,
+    seed: u64,
+    duration: f32,
+) -> NetworkGraph {
+    let mut ids = HashMap::new();
+    let mut nodes = Vec::new();
+    let mut node_id = |entity: Entity| {
+        *ids.entry(entity).or_insert_with(|| {
+            let (position, cooldown) = lumina
+                .get(entity)
+                .map_or((Vec2::ZERO, false), |(transform, cooldown)| {
+                    (transform.translation.xy(), cooldown)
+                });
+            let id = nodes.len();
+            nodes.push(GraphNode {
+                id,
+                x: position.x,
+                y: position.y,
+                kind: if cooldown {
+                    NodeKind::Cooldown
+                } else {
+                    NodeKind::Lumina
+                },
+            });
+            id
+        })
+    };
+    let edges = log
+        .links
+        .iter()
+        .map(|link| GraphEdge {
+            source: node_id(link.from),
+            target: node_id(link.to),
+            created: link.created,
+            pulses: link.pulses,
+        })
+        .collect();
+    NetworkGraph {
+        seed,
+        duration,
+        nodes,
+        edges,
+    }
+}
+
+/// Writes the network of the current run as JSON, GraphML and DOT.
+pub fn export_graph(
+    log: Res<LinkLog>,
+    lumina: Query<(&Transform, Has<Cooldown>), With<Lumina>>,
+    seed: Res<WorldSeed>,
+    stats: Res<RunStats>,
+) {
+    let graph = build_graph(&log, &lumina, seed.current(), stats.duration);
+    let name = format!("network-{:016x}-{:.0}s", graph.seed, graph.duration);
+    for (extension, contents) in [
+        ("json", graph.to_json()),
+        ("graphml", graph.to_graphml()),
+        ("dot", graph.to_dot()),
+    ] {
+        if let Some(path) = persistence::export(&format!("{name}.{extension}"), &contents) {
+            info!("exported network graph to {path:?}");
+        }
+    }
+}
+
+impl Plugin for GraphExportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Playing),
+            (
+                start_log,
+                record_authored_links
+                    .after(start_log)
+                    .after(spawn_authored_lumina)
+                    .run_if(resource_exists::<AuthoredMap>),
+            ),
+        )
+        .add_systems(
+            Update,
+            record_links
+                .in_set(Simulation::Rules)
+                .before(check_run)
+                .run_if(in_state(GameRunState::Playing)),
+        )
+        .add_systems(
+            OnEnter(GameRunState::Ending),
+            export_graph.run_if(not(resource_exists::<Playback>)),
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use bevy::{ecs::relationship::RelatedSpawner, ecs::spawn::SpawnWith, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{GameRunState, GameState, persistence};
+
+use super::{
+    challenge::Challenge,
+    chunks::LuminaNetwork,
+    game_loop::{GameData, RunStats, run_score},
+    leaderboard::{LEADERBOARD_SHOWN, Leaderboard, entry_lines},
+    localization::Localization,
+    mutators::ActiveMutators,
+    shop::UpgradeLevels,
+};
+
+pub struct HistoryPlugin;
+
+const HISTORY_SAVE: &str = "history";
+const CHART_BARS: usize = 40;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RunRecord {
+    pub score: u32,
+    pub links: u32,
+    pub distance: f32,
+    pub duration: f32,
+    /// Network credits held after the run paid out.
+    pub credits: u32,
+    pub upgrades: Vec<(String, u32)>,
+    #[serde(default)]
+    pub mutators: Vec<String>,
+}
+
+#[derive(Resource, Serialize, Deserialize, Default)]
+pub struct RunHistory {
+    pub runs: Vec<RunRecord>,
+    /// Network credits held after every change, including purchases, across
+    /// campaigns. A new campaign starts again from nothing.
+    #[serde(default)]
+    pub credits: Vec<u32>,
+}
+
+fn load_history(mut commands: Commands) {
+    commands.insert_resource(persistence::load::<RunHistory>(HISTORY_SAVE));
+}
+
+fn record_run(
+    mut history: ResMut<RunHistory>,
+    stats: Res<RunStats>,
+    network: Res<LuminaNetwork>,
+    data: Res<GameData>,
+    levels: Res<UpgradeLevels>,
+    mutators: Res<ActiveMutators>,
+) {
+    history.runs.push(RunRecord {
+        score: run_score(network.size, stats.max_distance),
+        links: network.size,
+        distance: stats.max_distance,
+        duration: stats.duration,
+        credits: data.network_credits,
+        upgrades: levels.held(),
+        mutators: mutators.ids().iter().map(|id| id.to_string()).collect(),
+    });
+    persistence::save(HISTORY_SAVE, history.as_ref());
+}
+
+fn record_credits(mut history: ResMut<RunHistory>, data: Res<GameData>) {
+    if history.credits.last() == Some(&data.network_credits) {
+        return;
+    }
+    history.credits.push(data.network_credits);
+    persistence::save(HISTORY_SAVE, history.as_ref());
+}
+
+fn back(_trigger: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.set_state(GameState::Shop);
+}
+
+fn chart(title: &str, values: Vec<u32>, color: Color, loc: &Localization) -> impl Bundle {
+    let max = values.iter().copied().max().unwrap_or(0).max(1);
+    (
+        Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(10.0),
+            ..default()
+        },
+        children![
+            Text::new(loc.tr_args(title, &[("max", max.into())])),
+            (
+                Node {
+                    width: Val::Px(500.0),
+                    height: Val::Px(250.0),
+                    border: UiRect::all(Val::Px(1.0)),
+                    padding: UiRect::all(Val::Px(5.0)),
+                    align_items: AlignItems::End,
+                    column_gap: Val::Px(2.0),
+                    ..default()
+                },
+                BorderColor(Color::WHITE),
+                Children::spawn(SpawnWith(move |parent: &mut RelatedSpawner<ChildOf>| {
+                    for value in values {
+                        parent.spawn((
+                            Node {
+                                flex_grow: 1.0,
+                                height: Val::Percent(100.0 * value as f32 / max as f32),
+                                ..default()
+                            },
+                            BackgroundColor(color),
+                        ));
+                    }
+                })),
+            )
+        ],
+    )
+}
+
+fn setup(
+    mut commands: Commands,
+    history: Res<RunHistory>,
+    leaderboard: Res<Leaderboard>,
+    loc: Res<Localization>,
+) {
+    let recent = &history.runs[history.runs.len().saturating_sub(CHART_BARS)..];
+    let links = recent.iter().map(|run| run.links).collect();
+    let credits = history.credits[history.credits.len().saturating_sub(CHART_BARS)..].to_vec();
+    let best = history.runs.iter().max_by_key(|run| run.score);
+    let summary = match best {
+        Some(best) => loc.tr_args(
+            "history-summary",
+            &[
+                ("runs", history.runs.len().into()),
+                ("score", best.score.into()),
+                ("links", best.links.into()),
+                ("distance", best.distance.into()),
+                ("duration", best.duration.into()),
+            ],
+        ),
+        None => loc.tr("history-empty"),
+    };
+    let campaign = leaderboard.campaign();
+    let best_runs = format!(
+        "{}\n{}",
+        loc.tr("leaderboard-campaign"),
+        entry_lines(&campaign[..campaign.len().min(LEADERBOARD_SHOWN)], &loc)
+    );
+
+    commands.spawn((Camera2d, StateScoped(GameState::History)));
+    commands
+        .spawn((
+            StateScoped(GameState::History),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(30.0),
+                ..default()
+            },
+            children![
+                (
+                    Text::new(summary),
+                    TextLayout::new_with_justify(JustifyText::Center),
+                ),
+                (
+                    Node {
+                        column_gap: Val::Px(40.0),
+                        ..default()
+                    },
+                    children![
+                        chart(
+                            "history-links-chart",
+                            links,
+                            Color::srgb(0.0, 0.6, 1.0),
+                            &loc
+                        ),
+                        chart(
+                            "history-credits-chart",
+                            credits,
+                            Color::srgb(1.0, 0.6, 0.0),
+                            &loc
+                        ),
+                    ],
+                ),
+                Text::new(best_runs),
+            ],
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        position_type: PositionType::Absolute,
+                        bottom: Val::Px(15.0),
+                        right: Val::Px(15.0),
+                        width: Val::Px(150.0),
+                        height: Val::Px(65.0),
+                        border: UiRect::all(Val::Px(1.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BorderColor(Color::WHITE),
+                    BorderRadius::all(Val::Px(5.0)),
+                    children![(Text::new(loc.tr("history-back")),)],
+                ))
+                .observe(back);
+        });
+}
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_history)
+            .add_systems(
+                OnEnter(GameRunState::Ending),
+                record_run.run_if(not(resource_exists::<Challenge>)),
+            )
+            .add_systems(Update, record_credits.run_if(resource_changed::<GameData>))
+            .add_systems(OnEnter(GameState::History), setup);
+    }
+}
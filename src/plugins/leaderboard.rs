@@ -0,0 +1,219 @@
+use std::cmp::Reverse;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{GameRunState, GameState, persistence};
+
+use super::{
+    challenge::{Challenge, ChallengeKind, current_day, format_day},
+    chunks::{LuminaNetwork, WorldSeed},
+    game_loop::{RunStats, run_score},
+    localization::Localization,
+    map::AuthoredMap,
+    mutators::{ActiveMutators, BaseScaling},
+    replay::{Playback, Recording},
+    shop::UpgradeLevels,
+    tutorial::Tutorial,
+};
+
+pub struct LeaderboardPlugin;
+
+const LEADERBOARD_SAVE: &str = "leaderboard";
+/// Entries kept per board, lower scores are dropped.
+const LEADERBOARD_KEPT: usize = 20;
+/// Challenges kept per kind, older ones are dropped with their entries.
+const CHALLENGE_BOARDS_KEPT: usize = 8;
+pub const LEADERBOARD_SHOWN: usize = 5;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LeaderboardMode {
+    Campaign,
+    Challenge(ChallengeKind),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LeaderboardEntry {
+    pub mode: LeaderboardMode,
+    /// Day the run was played, or the date of the challenge.
+    pub date: String,
+    pub score: u32,
+    pub links: u32,
+    pub distance: f32,
+    /// Upgrades the ship was fitted with. Challenges bring their own loadout,
+    /// which is part of the replay setup.
+    pub upgrades: Vec<(String, u32)>,
+    /// Seed of the world the run was played in.
+    pub seed: u64,
+    /// Save holding the input of the whole run, to check the score by
+    /// simulating it again.
+    pub replay: String,
+}
+
+impl LeaderboardEntry {
+    /// Campaign runs share one board, challenge runs compete with runs of the
+    /// same challenge.
+    fn same_board(&self, other: &LeaderboardEntry) -> bool {
+        self.mode == other.mode
+            && (self.mode == LeaderboardMode::Campaign || self.seed == other.seed)
+    }
+}
+
+/// Best local runs of every mode.
+#[derive(Resource, Serialize, Deserialize, Default)]
+pub struct Leaderboard {
+    pub entries: Vec<LeaderboardEntry>,
+    /// Number of the next replay save.
+    #[serde(default)]
+    next_replay: u32,
+}
+
+impl Leaderboard {
+    /// Entries matching `filter`, best first.
+    fn ranked(&self, filter: impl Fn(&LeaderboardEntry) -> bool) -> Vec<&LeaderboardEntry> {
+        let mut ranked: Vec<_> = self.entries.iter().filter(|entry| filter(entry)).collect();
+        ranked.sort_by_key(|entry| Reverse(entry.score));
+        ranked
+    }
+
+    /// Entries on the same board as `entry`, best first.
+    pub fn board(&self, entry: &LeaderboardEntry) -> Vec<&LeaderboardEntry> {
+        self.ranked(|other| other.same_board(entry))
+    }
+
+    pub fn campaign(&self) -> Vec<&LeaderboardEntry> {
+        self.ranked(|entry| entry.mode == LeaderboardMode::Campaign)
+    }
+
+    /// Position `entry` has on its board, starting at 1.
+    pub fn rank(&self, entry: &LeaderboardEntry) -> usize {
+        self.board(entry)
+            .iter()
+            .filter(|other| other.score > entry.score)
+            .count()
+            + 1
+    }
+
+    /// Adds `entry` and returns the entries that no longer make it onto
+    /// their board.
+    fn submit(&mut self, entry: LeaderboardEntry) -> Vec<LeaderboardEntry> {
+        self.entries.push(entry.clone());
+        let mut dropped: Vec<String> = self
+            .board(&entry)
+            .iter()
+            .skip(LEADERBOARD_KEPT)
+            .map(|other| other.replay.clone())
+            .collect();
+        if entry.mode != LeaderboardMode::Campaign {
+            // dates sort like the days they stand for
+            let mut dates: Vec<&str> = self
+                .entries
+                .iter()
+                .filter(|other| other.mode == entry.mode)
+                .map(|other| other.date.as_str())
+                .collect();
+            dates.sort_unstable_by(|a, b| b.cmp(a));
+            dates.dedup();
+            if let Some(oldest) = dates.get(CHALLENGE_BOARDS_KEPT - 1) {
+                dropped.extend(
+                    self.entries
+                        .iter()
+                        .filter(|other| other.mode == entry.mode && other.date.as_str() < *oldest)
+                        .map(|other| other.replay.clone()),
+                );
+            }
+        }
+        self.entries
+            .extract_if(.., |other| dropped.contains(&other.replay))
+            .collect()
+    }
+}
+
+/// The entry of the run that just ended.
+#[derive(Resource)]
+pub struct LatestEntry(pub LeaderboardEntry);
+
+/// One `leaderboard-entry` line per entry, ranked in the given order.
+pub fn entry_lines(entries: &[&LeaderboardEntry], loc: &Localization) -> String {
+    entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            loc.tr_args(
+                "leaderboard-entry",
+                &[
+                    ("rank", (index + 1).into()),
+                    ("score", entry.score.into()),
+                    ("links", entry.links.into()),
+                    ("distance", entry.distance.into()),
+                    ("date", entry.date.clone().into()),
+                ],
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn load_leaderboard(mut commands: Commands) {
+    commands.insert_resource(persistence::load::<Leaderboard>(LEADERBOARD_SAVE));
+}
+
+fn submit_run(
+    mut commands: Commands,
+    mut leaderboard: ResMut<Leaderboard>,
+    challenge: Option<Res<Challenge>>,
+    stats: Res<RunStats>,
+    network: Res<LuminaNetwork>,
+    levels: Res<UpgradeLevels>,
+    recording: Res<Recording>,
+    seed: Res<WorldSeed>,
+    base: Res<BaseScaling>,
+    mutators: Res<ActiveMutators>,
+) {
+    let replay = format!("replays/{}", leaderboard.next_replay);
+    leaderboard.next_replay += 1;
+    persistence::save_compact(&replay, &recording.replay(&seed, &base, &mutators));
+    let entry = LeaderboardEntry {
+        mode: challenge
+            .as_ref()
+            .map_or(LeaderboardMode::Campaign, |challenge| {
+                LeaderboardMode::Challenge(challenge.kind)
+            }),
+        date: challenge
+            .as_ref()
+            .map_or_else(|| format_day(current_day()), |challenge| challenge.date()),
+        score: run_score(network.size, stats.max_distance),
+        links: network.size,
+        distance: stats.max_distance,
+        upgrades: match challenge {
+            Some(_) => Vec::new(),
+            None => levels.held(),
+        },
+        seed: seed.current(),
+        replay,
+    };
+    for dropped in leaderboard.submit(entry.clone()) {
+        persistence::remove(&dropped.replay);
+    }
+    persistence::save(LEADERBOARD_SAVE, leaderboard.as_ref());
+    commands.insert_resource(LatestEntry(entry));
+}
+
+fn clear_latest_entry(mut commands: Commands) {
+    commands.remove_resource::<LatestEntry>();
+}
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_leaderboard)
+            .add_systems(OnEnter(GameState::Playing), clear_latest_entry)
+            .add_systems(
+                OnEnter(GameRunState::Ending),
+                submit_run
+                    .run_if(not(resource_exists::<Playback>))
+                    .run_if(not(resource_exists::<Tutorial>))
+                    // replays don't carry the map
+                    .run_if(not(resource_exists::<AuthoredMap>)),
+            );
+    }
+}
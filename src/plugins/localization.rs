@@ -0,0 +1,289 @@
+use bevy::prelude::*;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue, concurrent::FluentBundle};
+use serde::{Deserialize, Serialize};
+
+use super::settings::Settings;
+
+pub struct LocalizationPlugin;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    pub const ALL: &[Language] = &[Language::English, Language::German];
+
+    fn code(self) -> &'static str {
+        match self {
+            Language::English => "en-US",
+            Language::German => "de",
+        }
+    }
+
+    fn source(self) -> &'static str {
+        match self {
+            Language::English => include_str!("../../assets/locales/en-US.ftl"),
+            Language::German => include_str!("../../assets/locales/de.ftl"),
+        }
+    }
+
+    pub fn next(self) -> Language {
+        let index = Language::ALL.iter().position(|l| *l == self).unwrap_or(0);
+        Language::ALL[(index + 1) % Language::ALL.len()]
+    }
+}
+
+fn bundle(language: Language) -> FluentBundle<FluentResource> {
+    let resource = FluentResource::try_new(language.source().to_string()).unwrap_or_else(
+        |(resource, errors)| {
+            error!("errors in {} messages: {errors:?}", language.code());
+            resource
+        },
+    );
+    let mut bundle = FluentBundle::new_concurrent(vec![language.code().parse().unwrap()]);
+    // the isolation marks render as boxes in the UI font
+    bundle.set_use_isolating(false);
+    bundle.add_builtins().unwrap();
+    bundle.set_formatter(Some(match language {
+        Language::English => format_number::<_, '.'>,
+        Language::German => format_number::<_, ','>,
+    }));
+    if let Err(errors) = bundle.add_resource(resource) {
+        error!("errors in {} messages: {errors:?}", language.code());
+    }
+    bundle
+}
+
+/// Formats numbers with the fraction digits the messages ask for through
+/// `NUMBER`, which fluent itself leaves to the application.
+fn format_number<M, const DECIMAL_POINT: char>(value: &FluentValue, _intls: &M) -> Option<String> {
+    let FluentValue::Number(number) = value else {
+        return None;
+    };
+    let mut text = match number.options.maximum_fraction_digits {
+        Some(digits) => format!("{:.digits$}", number.value),
+        None => number.value.to_string(),
+    };
+    let min_digits = number.options.minimum_fraction_digits.unwrap_or(0);
+    if let Some(point) = text.find('.') {
+        let keep = text.trim_end_matches('0').len().max(point + 1 + min_digits);
+        text.truncate(keep);
+        text = text
+            .trim_end_matches('.')
+            .replace('.', &DECIMAL_POINT.to_string());
+    } else if min_digits > 0 {
+        text = format!("{text}{DECIMAL_POINT}{}", "0".repeat(min_digits));
+    }
+    Some(text)
+}
+
+/// All user-facing text goes through this resource. Messages missing from the
+/// selected language fall back to English, and then to the message id.
+#[derive(Resource)]
+pub struct Localization {
+    language: Language,
+    bundle: FluentBundle<FluentResource>,
+    fallback: FluentBundle<FluentResource>,
+}
+
+impl Localization {
+    pub fn new(language: Language) -> Self {
+        Self {
+            language,
+            bundle: bundle(language),
+            fallback: bundle(Language::English),
+        }
+    }
+
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    pub fn tr(&self, id: &str) -> String {
+        self.tr_args(id, &[])
+    }
+
+    pub fn tr_args(&self, id: &str, args: &[(&str, FluentValue)]) -> String {
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+        for bundle in [&self.bundle, &self.fallback] {
+            if let Some(pattern) = bundle.get_message(id).and_then(|m| m.value()) {
+                let mut errors = vec![];
+                let text = bundle.format_pattern(pattern, Some(&fluent_args), &mut errors);
+                if !errors.is_empty() {
+                    warn!("errors formatting {id}: {errors:?}");
+                }
+                return text.into_owned();
+            }
+        }
+        warn!("missing message {id}");
+        id.to_string()
+    }
+}
+
+impl FromWorld for Localization {
+    fn from_world(world: &mut World) -> Self {
+        // `apply_language` catches up should the settings not be loaded yet
+        let language = world
+            .get_resource::<Settings>()
+            .map(|settings| settings.language)
+            .unwrap_or_default();
+        Self::new(language)
+    }
+}
+
+fn apply_language(mut loc: ResMut<Localization>, settings: Res<Settings>) {
+    if loc.language() != settings.language {
+        *loc = Localization::new(settings.language);
+    }
+}
+
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut App) {
+        // the menu of the initial state is set up before any startup system runs
+        app.init_resource::<Localization>().add_systems(
+            PreUpdate,
+            apply_language.run_if(resource_changed::<Settings>),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::plugins::{
+        achievements::ACHIEVEMENTS, challenge::ChallengeKind, contracts::CONTRACTS,
+        mutators::MUTATORS, palette::ColorMode, shop::UPGRADES, tutorial::TutorialStep,
+    };
+
+    fn message_ids(source: &str) -> BTreeSet<String> {
+        source
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_alphabetic()))
+            .filter_map(|line| line.split_once(" ="))
+            .map(|(id, _)| id.trim().to_string())
+            .collect()
+    }
+
+    /// Message ids passed as literals to `tr` and `tr_args` anywhere in the sources.
+    fn ids_in_sources(dir: &std::path::Path, ids: &mut BTreeSet<String>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                ids_in_sources(&path, ids);
+                continue;
+            }
+            let source = std::fs::read_to_string(&path).unwrap();
+            for call in [".tr(", ".tr_args("] {
+                for (index, _) in source.match_indices(call) {
+                    let rest = source[index + call.len()..].trim_start();
+                    let Some(literal) = rest.strip_prefix('"') else {
+                        continue;
+                    };
+                    let id = &literal[..literal.find('"').unwrap()];
+                    // skips the call patterns above
+                    if id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+                        ids.insert(id.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn locales_parse() {
+        for language in Language::ALL {
+            assert!(
+                FluentResource::try_new(language.source().to_string()).is_ok(),
+                "{} messages don't parse",
+                language.code()
+            );
+        }
+    }
+
+    #[test]
+    fn numbers_are_formatted_by_the_messages() {
+        let args = [("energy", 12.5.into()), ("distance", 1234.56.into())];
+        assert_eq!(
+            Localization::new(Language::English).tr_args("energy-debug", &args),
+            "Energy: 12.5\nDistance: 1235"
+        );
+        assert_eq!(
+            Localization::new(Language::German).tr_args("energy-debug", &args),
+            "Energie: 12,5\nEntfernung: 1235"
+        );
+        let args = [("multiplier", 2.0.into())];
+        assert_eq!(
+            Localization::new(Language::German).tr_args("mutators-title", &args),
+            "Mutatoren (Credits x2,00)"
+        );
+    }
+
+    #[test]
+    fn no_missing_keys() {
+        let english = message_ids(Language::English.source());
+
+        let mut required = BTreeSet::new();
+        ids_in_sources(
+            &std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+            &mut required,
+        );
+        for upgrade in UPGRADES {
+            required.insert(format!("upgrade-{}", upgrade.id()));
+        }
+        for achievement in ACHIEVEMENTS {
+            required.insert(format!("achievement-{}", achievement.id));
+            required.insert(format!("achievement-{}-description", achievement.id));
+        }
+        for mutator in MUTATORS {
+            required.insert(format!("mutator-{}", mutator.id()));
+            required.insert(format!("mutator-{}-description", mutator.id()));
+        }
+        for contract in CONTRACTS {
+            required.insert(format!("contract-{}", contract.id()));
+        }
+        for kind in ChallengeKind::ALL {
+            required.insert(format!("challenge-{}", kind.id()));
+        }
+        for mode in ColorMode::ALL {
+            required.insert(format!("color-mode-{}", mode.id()));
+        }
+        for step in TutorialStep::ALL {
+            required.insert(format!("tutorial-{}", step.id()));
+        }
+        let story = std::fs::read_to_string(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/campaign.story.ron"),
+        )
+        .unwrap();
+        for (index, _) in story.match_indices("id: \"") {
+            let id = &story[index + 5..];
+            required.insert(format!("story-{}", &id[..id.find('"').unwrap()]));
+        }
+
+        let missing: Vec<_> = required.difference(&english).collect();
+        assert!(missing.is_empty(), "missing English messages: {missing:?}");
+
+        for language in Language::ALL {
+            let ids = message_ids(language.source());
+            let missing: Vec<_> = english.difference(&ids).collect();
+            assert!(
+                missing.is_empty(),
+                "missing {} messages: {missing:?}",
+                language.code()
+            );
+            let extra: Vec<_> = ids.difference(&english).collect();
+            assert!(
+                extra.is_empty(),
+                "{} messages not in English: {extra:?}",
+                language.code()
+            );
+        }
+    }
+}
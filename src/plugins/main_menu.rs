@@ -1,21 +1,62 @@
 use bevy::prelude::*;
 
-use crate::AppState;
+use crate::{AppState, MenuState};
+
+use super::{
+    challenge::{start_daily, start_weekly},
+    localization::Localization,
+    settings::open_settings,
+};
 
 pub struct MainMenuPlugin;
 
+#[derive(Component)]
+struct MainMenuRoot;
+
 fn start_game(_trigger: Trigger<Pointer<Click>>, mut commands: Commands) {
     commands.set_state(AppState::InGame);
 }
 
-fn setup_menu(mut commands: Commands) {
+fn show_achievements(_trigger: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.set_state(MenuState::Achievements);
+}
+
+fn rebuild_menu(mut commands: Commands, menu: Single<Entity, With<MainMenuRoot>>) {
+    commands.entity(*menu).despawn();
+    commands.run_system_cached(setup_menu);
+}
+
+fn menu_button(text: String) -> impl Bundle {
+    (
+        Button,
+        Node {
+            width: Val::Px(200.0),
+            height: Val::Px(50.0),
+            border: UiRect::all(Val::Px(1.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BorderColor(Color::WHITE),
+        BorderRadius::all(Val::Px(5.0)),
+        children![(Text::new(text), TextColor(Color::srgb(0.9, 0.9, 0.9)))],
+    )
+}
+
+fn setup_camera(mut commands: Commands) {
     commands.spawn((Camera2d, StateScoped(AppState::MainMenu)));
+}
+
+fn setup_menu(mut commands: Commands, loc: Res<Localization>) {
     commands
         .spawn((
-            StateScoped(AppState::MainMenu),
+            MainMenuRoot,
+            StateScoped(MenuState::Main),
             Node {
                 width: Val::Percent(100.0),
                 height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(10.0),
                 align_items: AlignItems::Center,
                 justify_content: JustifyContent::Center,
                 ..default()
@@ -36,17 +77,35 @@ fn setup_menu(mut commands: Commands) {
                     BorderColor(Color::WHITE),
                     BorderRadius::MAX,
                     children![(
-                        Text::new("Start"),
+                        Text::new(loc.tr("menu-start")),
                         TextColor(Color::srgb(0.9, 0.9, 0.9)),
                         TextShadow::default(),
                     )],
                 ))
                 .observe(start_game);
+            parent
+                .spawn(menu_button(loc.tr("menu-daily-challenge")))
+                .observe(start_daily);
+            parent
+                .spawn(menu_button(loc.tr("menu-weekly-challenge")))
+                .observe(start_weekly);
+            parent
+                .spawn(menu_button(loc.tr("menu-achievements")))
+                .observe(show_achievements);
+            parent
+                .spawn(menu_button(loc.tr("menu-settings")))
+                .observe(open_settings);
         });
 }
 
 impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::MainMenu), setup_menu);
+        app.add_systems(OnEnter(AppState::MainMenu), setup_camera)
+            .add_systems(OnEnter(MenuState::Main), setup_menu)
+            .add_systems(
+                Update,
+                rebuild_menu
+                    .run_if(in_state(MenuState::Main).and(resource_changed::<Localization>)),
+            );
     }
 }
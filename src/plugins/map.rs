@@ -0,0 +1,169 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{GameRunState, GameState};
+
+use super::{
+    game_loop::{Simulation, check_run},
+    palette::Palette,
+    ship::Ship,
+};
+
+pub struct MapPlugin;
+
+// Maps are RON documents like
+//
+//     (
+//         bounds: (min: (-2000.0, -2000.0), max: (2000.0, 2000.0)),
+//         lumina: [(position: (0.0, 150.0)), (position: (600.0, 0.0), kind: Resting)],
+//         hazards: [(position: (300.0, 300.0), radius: 200.0, drain_per_sec: 50.0)],
+//         links: [(0, 1)],
+//         start: (0.0, -300.0),
+//     )
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct MapBounds {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl MapBounds {
+    pub fn contains(&self, position: Vec2) -> bool {
+        position.cmpge(self.min).all() && position.cmple(self.max).all()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LuminaKind {
+    #[default]
+    Normal,
+    /// Starts the run in cooldown.
+    Resting,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MapLumina {
+    pub position: Vec2,
+    #[serde(default)]
+    pub kind: LuminaKind,
+}
+
+/// Area that drains the ship's battery while it is inside.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Hazard {
+    pub position: Vec2,
+    pub radius: f32,
+    pub drain_per_sec: f32,
+}
+
+/// Hand-authored layout. Inside `bounds` only the listed Lumina exist,
+/// outside of it the world is generated as usual.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Map {
+    pub bounds: MapBounds,
+    pub lumina: Vec<MapLumina>,
+    #[serde(default)]
+    pub hazards: Vec<Hazard>,
+    /// Links present from the start, as indices into `lumina`.
+    #[serde(default)]
+    pub links: Vec<(usize, usize)>,
+    /// Where the ship starts.
+    #[serde(default)]
+    pub start: Vec2,
+}
+
+impl Default for Map {
+    fn default() -> Self {
+        Map {
+            bounds: MapBounds {
+                min: Vec2::splat(-2500.0),
+                max: Vec2::splat(2500.0),
+            },
+            lumina: Vec::new(),
+            hazards: Vec::new(),
+            links: Vec::new(),
+            start: Vec2::ZERO,
+        }
+    }
+}
+
+impl Map {
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .expect("map is plain data")
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write(&self, path: &str) -> Result<(), String> {
+        std::fs::write(path, self.to_ron()).map_err(|err| err.to_string())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn read(path: &str) -> Result<Map, String> {
+        let contents = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+        let map: Map = ron::from_str(&contents).map_err(|err| err.to_string())?;
+        if let Some((from, to)) = map
+            .links
+            .iter()
+            .find(|(from, to)| *from >= map.lumina.len() || *to >= map.lumina.len() || from == to)
+        {
+            return Err(format!("invalid link between Lumina {from} and {to}"));
+        }
+        Ok(map)
+    }
+}
+
+/// Present while runs are played on an authored map.
+#[derive(Resource)]
+pub struct AuthoredMap(pub Map);
+
+/// File the authored map was read from.
+#[derive(Resource)]
+pub struct MapFile(pub String);
+
+pub fn spawn_hazards(mut commands: Commands, map: Res<AuthoredMap>) {
+    for hazard in &map.0.hazards {
+        commands.spawn((
+            hazard.clone(),
+            StateScoped(GameState::Playing),
+            Transform::from_translation(hazard.position.extend(0.0)),
+        ));
+    }
+}
+
+fn drain_battery(time: Res<Time>, hazards: Query<&Hazard>, ship: Single<(&Transform, &mut Ship)>) {
+    let (transform, mut ship) = ship.into_inner();
+    let position = transform.translation.xy();
+    for hazard in &hazards {
+        if position.distance(hazard.position) < hazard.radius {
+            ship.energy = 0.0f32.max(ship.energy - hazard.drain_per_sec * time.delta_secs());
+        }
+    }
+}
+
+fn draw_hazards(hazards: Query<&Hazard>, palette: Res<Palette>, mut gizmos: Gizmos) {
+    for hazard in &hazards {
+        gizmos.circle_2d(
+            Isometry2d::from_translation(hazard.position),
+            hazard.radius,
+            palette.attach_out_of_range,
+        );
+    }
+}
+
+impl Plugin for MapPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Playing),
+            spawn_hazards.run_if(resource_exists::<AuthoredMap>),
+        )
+        .add_systems(
+            Update,
+            (
+                drain_battery.in_set(Simulation::Rules).before(check_run),
+                draw_hazards,
+            )
+                .run_if(in_state(GameRunState::Playing)),
+        );
+    }
+}
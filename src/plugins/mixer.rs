@@ -0,0 +1,304 @@
+use bevy::prelude::*;
+
+pub struct MixerPlugin;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Sfx {
+    Generate,
+    Reflect,
+    Propagate,
+    Charge,
+}
+
+/// Request to play a sound effect. Positioned sounds are panned relative to
+/// the ship and dropped when they are too far away to hear.
+#[derive(Event)]
+pub struct PlaySfx {
+    pub sfx: Sfx,
+    pub position: Option<Vec2>,
+    pub volume: f32,
+}
+
+impl Plugin for MixerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PlaySfx>();
+        #[cfg(feature = "audio")]
+        app.add_plugins(playback::PlaybackPlugin);
+    }
+}
+
+/// Plays the requested sounds and the music, left out of builds without the
+/// `audio` feature.
+#[cfg(feature = "audio")]
+mod playback {
+    use std::{f32::consts::TAU, time::Duration};
+
+    use bevy::{
+        audio::{AddAudioSource, Source, SpatialScale, Volume},
+        platform::collections::HashMap,
+        prelude::*,
+    };
+
+    use crate::{
+        GameState,
+        plugins::{chunks::LuminaNetwork, settings::Settings, ship::Ship},
+    };
+
+    use super::{PlaySfx, Sfx};
+
+    pub struct PlaybackPlugin;
+
+    impl Sfx {
+        /// Most instances of this sound that may play at once.
+        fn max_voices(self) -> usize {
+            match self {
+                Sfx::Generate => 2,
+                Sfx::Reflect | Sfx::Propagate => 3,
+                Sfx::Charge => 2,
+            }
+        }
+
+        /// Minimum seconds between two starts of this sound.
+        fn cooldown(self) -> f32 {
+            match self {
+                Sfx::Generate => 0.15,
+                Sfx::Reflect | Sfx::Propagate => 0.1,
+                Sfx::Charge => 0.2,
+            }
+        }
+    }
+
+    const MAX_DISTANCE: f32 = 1000.0;
+    /// World units per unit of spatial audio distance. Sounds within this range of
+    /// the ship play at full volume, further ones fall off.
+    const SPATIAL_RANGE: f32 = 500.0;
+    const EAR_GAP: f32 = 2.0;
+
+    #[derive(Resource)]
+    struct SfxResources {
+        sounds: HashMap<Sfx, Handle<AudioSource>>,
+    }
+
+    #[derive(Resource, Default)]
+    struct Mixer {
+        last_played: HashMap<Sfx, f32>,
+    }
+
+    #[derive(Component)]
+    struct Voice(Sfx);
+
+    fn setup(mut commands: Commands, server: Res<AssetServer>, mut drones: ResMut<Assets<Drone>>) {
+        commands.insert_resource(SfxResources {
+            sounds: HashMap::from_iter([
+                (Sfx::Generate, server.load("generate.ogg")),
+                (Sfx::Reflect, server.load("reflect.ogg")),
+                (Sfx::Propagate, server.load("propagate.ogg")),
+                (Sfx::Charge, server.load("charge.ogg")),
+            ]),
+        });
+        commands.insert_resource(MusicResources {
+            layers: MUSIC_LAYERS
+                .iter()
+                .map(|layer| drones.add(Drone { frequency: layer.0 }))
+                .collect(),
+        });
+    }
+
+    fn add_listener(trigger: Trigger<OnAdd, Ship>, mut commands: Commands) {
+        commands
+            .entity(trigger.target())
+            .insert(SpatialListener::new(EAR_GAP));
+    }
+
+    fn play_sfx(
+        mut commands: Commands,
+        mut events: EventReader<PlaySfx>,
+        mut mixer: ResMut<Mixer>,
+        voices: Query<&Voice>,
+        listener: Option<Single<&GlobalTransform, With<SpatialListener>>>,
+        time: Res<Time>,
+        settings: Res<Settings>,
+        resources: Res<SfxResources>,
+    ) {
+        let listener = listener.map(|listener| listener.translation().xy());
+        // only the closest request of each kind is considered per frame
+        let mut requests: HashMap<Sfx, (f32, &PlaySfx)> = HashMap::default();
+        for event in events.read() {
+            let distance = match (event.position, listener) {
+                (Some(position), Some(listener)) => position.distance(listener),
+                _ => 0.0,
+            };
+            if distance > MAX_DISTANCE {
+                continue;
+            }
+            if requests
+                .get(&event.sfx)
+                .is_none_or(|(closest, _)| distance < *closest)
+            {
+                requests.insert(event.sfx, (distance, event));
+            }
+        }
+
+        let now = time.elapsed_secs();
+        for (sfx, (_, event)) in requests {
+            if mixer
+                .last_played
+                .get(&sfx)
+                .is_some_and(|last| now - last < sfx.cooldown())
+            {
+                continue;
+            }
+            if voices.iter().filter(|voice| voice.0 == sfx).count() >= sfx.max_voices() {
+                continue;
+            }
+            mixer.last_played.insert(sfx, now);
+            let playback = PlaybackSettings::DESPAWN
+                .with_volume(Volume::Linear(event.volume * settings.sfx_volume));
+            let mut voice =
+                commands.spawn((Voice(sfx), AudioPlayer::new(resources.sounds[&sfx].clone())));
+            match (event.position, listener) {
+                (Some(position), Some(_)) => {
+                    voice.insert((
+                        playback
+                            .with_spatial(true)
+                            .with_spatial_scale(SpatialScale::new_2d(1.0 / SPATIAL_RANGE)),
+                        Transform::from_translation(position.extend(0.0)),
+                    ));
+                }
+                _ => {
+                    voice.insert(playback);
+                }
+            }
+        }
+    }
+
+    /// A slowly beating sine tone, used to build up the ambient music in layers.
+    #[derive(Asset, TypePath)]
+    struct Drone {
+        frequency: f32,
+    }
+
+    struct DroneDecoder {
+        sample_rate: u32,
+        /// Cycles per sample of the tone, its detuned copy and the swell.
+        steps: [f32; 3],
+        /// Where each of them is in its cycle, kept in [0, 1) so that precision
+        /// doesn't degrade however long the drone plays.
+        phases: [f32; 3],
+    }
+
+    const DRONE_SAMPLE_RATE: u32 = 44_100;
+    const BEAT_HZ: f32 = 0.15;
+
+    impl Iterator for DroneDecoder {
+        type Item = f32;
+
+        fn next(&mut self) -> Option<f32> {
+            for (phase, step) in self.phases.iter_mut().zip(self.steps) {
+                *phase = (*phase + step).fract();
+            }
+            let [tone, detuned, swell] = self.phases.map(|phase| (TAU * phase).sin());
+            Some(0.2 * (tone + 0.5 * detuned) * (0.6 + 0.4 * swell))
+        }
+    }
+
+    impl Source for DroneDecoder {
+        fn current_frame_len(&self) -> Option<usize> {
+            None
+        }
+        fn channels(&self) -> u16 {
+            1
+        }
+        fn sample_rate(&self) -> u32 {
+            self.sample_rate
+        }
+        fn total_duration(&self) -> Option<Duration> {
+            None
+        }
+    }
+
+    impl Decodable for Drone {
+        type DecoderItem = f32;
+        type Decoder = DroneDecoder;
+
+        fn decoder(&self) -> DroneDecoder {
+            DroneDecoder {
+                sample_rate: DRONE_SAMPLE_RATE,
+                steps: [self.frequency, self.frequency * 1.003, BEAT_HZ]
+                    .map(|hz| hz / DRONE_SAMPLE_RATE as f32),
+                phases: [0.0; 3],
+            }
+        }
+    }
+
+    /// Frequency of each music layer and the network size at which it fades in.
+    const MUSIC_LAYERS: &[(f32, u32)] =
+        &[(55.0, 0), (82.5, 3), (110.0, 8), (164.8, 15), (220.0, 25)];
+    const MUSIC_FADE_PER_SEC: f32 = 0.2;
+
+    #[derive(Resource)]
+    struct MusicResources {
+        layers: Vec<Handle<Drone>>,
+    }
+
+    #[derive(Component)]
+    struct MusicLayer {
+        threshold: u32,
+        level: f32,
+    }
+
+    fn start_music(mut commands: Commands, resources: Res<MusicResources>) {
+        for (handle, (_, threshold)) in resources.layers.iter().zip(MUSIC_LAYERS) {
+            commands.spawn((
+                Name::from("Music"),
+                StateScoped(GameState::Playing),
+                MusicLayer {
+                    threshold: *threshold,
+                    level: 0.0,
+                },
+                AudioPlayer(handle.clone()),
+                PlaybackSettings::LOOP.with_volume(Volume::Linear(0.0)),
+            ));
+        }
+    }
+
+    fn update_music(
+        layers: Query<(&mut MusicLayer, &mut AudioSink)>,
+        network: Option<Res<LuminaNetwork>>,
+        settings: Res<Settings>,
+        time: Res<Time>,
+    ) {
+        let size = network.map_or(0, |network| network.size);
+        for (mut layer, mut sink) in layers {
+            let target = if size >= layer.threshold { 1.0 } else { 0.0 };
+            let step = MUSIC_FADE_PER_SEC * time.delta_secs();
+            layer.level += (target - layer.level).clamp(-step, step);
+            // the sink volume replaces the global volume, so apply the master volume here
+            sink.set_volume(Volume::Linear(
+                layer.level * settings.music_volume * settings.master_volume,
+            ));
+        }
+    }
+
+    fn apply_volume(mut commands: Commands, settings: Res<Settings>) {
+        commands.insert_resource(GlobalVolume::new(Volume::Linear(settings.master_volume)));
+    }
+
+    impl Plugin for PlaybackPlugin {
+        fn build(&self, app: &mut App) {
+            app.add_audio_source::<Drone>()
+                .init_resource::<Mixer>()
+                .add_observer(add_listener)
+                .add_systems(Startup, setup)
+                .add_systems(OnEnter(GameState::Playing), start_music)
+                .add_systems(
+                    Update,
+                    (
+                        play_sfx,
+                        update_music,
+                        apply_volume.run_if(resource_changed::<Settings>),
+                    ),
+                );
+        }
+    }
+}
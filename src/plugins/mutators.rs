@@ -0,0 +1,312 @@
+use bevy::prelude::*;
+
+use crate::{AppState, GameRunState, GameState};
+
+use super::{
+    chunks::LinkCreatedEvent,
+    game_loop::{Simulation, check_run},
+    localization::Localization,
+    scaling::Scaling,
+    ship::Ship,
+};
+
+pub struct MutatorsPlugin;
+
+/// Optional rule change for a single run, chosen in the shop. Name and
+/// description are the `mutator-<id>` and `mutator-<id>-description` messages.
+pub trait Mutator {
+    fn id(&self) -> &'static str;
+    /// Network credits earned by a run are multiplied by this.
+    fn credit_multiplier(&self) -> f32;
+    fn apply(&self, _scaling: &mut Scaling) {}
+}
+
+struct DoubleDrainMutator;
+
+impl Mutator for DoubleDrainMutator {
+    fn id(&self) -> &'static str {
+        "double_drain"
+    }
+    fn credit_multiplier(&self) -> f32 {
+        1.5
+    }
+    fn apply(&self, scaling: &mut Scaling) {
+        scaling.life_support_per_distance *= 2.0;
+    }
+}
+
+struct NoBrakingMutator;
+
+impl Mutator for NoBrakingMutator {
+    fn id(&self) -> &'static str {
+        "no_braking"
+    }
+    fn credit_multiplier(&self) -> f32 {
+        1.25
+    }
+    fn apply(&self, scaling: &mut Scaling) {
+        scaling.can_brake = false;
+    }
+}
+
+/// Handled by `charge_links`.
+struct LinkCostMutator;
+
+impl Mutator for LinkCostMutator {
+    fn id(&self) -> &'static str {
+        "link_cost"
+    }
+    fn credit_multiplier(&self) -> f32 {
+        1.3
+    }
+}
+
+/// Handled by `setup_fog`.
+struct FogMutator;
+
+impl Mutator for FogMutator {
+    fn id(&self) -> &'static str {
+        "fog"
+    }
+    fn credit_multiplier(&self) -> f32 {
+        1.2
+    }
+}
+
+struct FragileNodesMutator;
+
+impl Mutator for FragileNodesMutator {
+    fn id(&self) -> &'static str {
+        "fragile_nodes"
+    }
+    fn credit_multiplier(&self) -> f32 {
+        1.3
+    }
+    fn apply(&self, scaling: &mut Scaling) {
+        scaling.lumina_cooldown_per_generation *= 2.0;
+        scaling.lumina_resume_per_sec *= 0.5;
+    }
+}
+
+pub const MUTATORS: &[&dyn Mutator] = &[
+    &DoubleDrainMutator,
+    &NoBrakingMutator,
+    &LinkCostMutator,
+    &FogMutator,
+    &FragileNodesMutator,
+];
+
+const LINK_ENERGY_COST: f32 = 100.0;
+const FOG_RADIUS: f32 = 600.0;
+
+/// Mutators chosen for the next run. They stay selected between runs.
+#[derive(Resource, Default)]
+pub struct ActiveMutators {
+    ids: Vec<&'static str>,
+}
+
+impl ActiveMutators {
+    pub fn is_active(&self, id: &str) -> bool {
+        self.ids.contains(&id)
+    }
+
+    /// The mutators with the given ids, unknown ids are ignored.
+    pub fn from_ids(ids: &[String]) -> Self {
+        Self {
+            ids: MUTATORS
+                .iter()
+                .map(|mutator| mutator.id())
+                .filter(|id| ids.iter().any(|other| other == id))
+                .collect(),
+        }
+    }
+
+    pub fn ids(&self) -> &[&'static str] {
+        &self.ids
+    }
+
+    pub fn credit_multiplier(&self) -> f32 {
+        MUTATORS
+            .iter()
+            .filter(|mutator| self.is_active(mutator.id()))
+            .map(|mutator| mutator.credit_multiplier())
+            .product()
+    }
+
+    fn toggle(&mut self, id: &'static str) {
+        if let Some(index) = self.ids.iter().position(|active| *active == id) {
+            self.ids.remove(index);
+        } else {
+            self.ids.push(id);
+        }
+    }
+}
+
+pub fn mutator_active(id: &'static str) -> impl Fn(Option<Res<ActiveMutators>>) -> bool {
+    // conditions are checked before a run has been started too
+    move |mutators: Option<Res<ActiveMutators>>| {
+        mutators.is_some_and(|mutators| mutators.is_active(id))
+    }
+}
+
+/// Scaling from before the mutators were applied, restored when the run ends.
+#[derive(Resource)]
+pub struct BaseScaling(pub Scaling);
+
+fn setup_game(mut commands: Commands) {
+    commands.insert_resource(ActiveMutators::default());
+}
+
+pub fn apply_mutators(
+    mut commands: Commands,
+    mut scaling: ResMut<Scaling>,
+    mutators: Res<ActiveMutators>,
+) {
+    commands.insert_resource(BaseScaling(scaling.clone()));
+    for mutator in MUTATORS {
+        if mutators.is_active(mutator.id()) {
+            mutator.apply(scaling.as_mut());
+        }
+    }
+}
+
+fn restore_scaling(
+    mut commands: Commands,
+    base: Option<Res<BaseScaling>>,
+    mut scaling: ResMut<Scaling>,
+) {
+    if let Some(base) = base {
+        *scaling = base.0.clone();
+        commands.remove_resource::<BaseScaling>();
+    }
+}
+
+fn charge_links(mut created: EventReader<LinkCreatedEvent>, mut ship: Single<&mut Ship>) {
+    for _ in created.read() {
+        ship.energy = (ship.energy - LINK_ENERGY_COST).max(0.0);
+    }
+}
+
+fn setup_fog(
+    mut commands: Commands,
+    ship: Single<Entity, Added<Ship>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    // two rings so the edge of the visible area isn't a hard line
+    commands.entity(*ship).with_children(|parent| {
+        parent.spawn((
+            Mesh2d(meshes.add(Annulus::new(FOG_RADIUS, FOG_RADIUS * 1.3))),
+            MeshMaterial2d(materials.add(Color::srgba(0.0, 0.0, 0.0, 0.6))),
+            Transform::from_xyz(0.0, 0.0, 1.0),
+        ));
+        parent.spawn((
+            Mesh2d(meshes.add(Annulus::new(FOG_RADIUS * 1.3, FOG_RADIUS * 20.0))),
+            MeshMaterial2d(materials.add(Color::BLACK)),
+            Transform::from_xyz(0.0, 0.0, 1.0),
+        ));
+    });
+}
+
+#[derive(Component)]
+struct MutatorPanel;
+
+fn mutator_clicked(
+    trigger: Trigger<Pointer<Click>>,
+    button: Query<&MutatorButton>,
+    mut mutators: ResMut<ActiveMutators>,
+    mut commands: Commands,
+) {
+    if let Ok(button) = button.get(trigger.target()) {
+        mutators.toggle(button.0);
+        commands.run_system_cached(rebuild_panel);
+    }
+}
+
+#[derive(Component)]
+struct MutatorButton(&'static str);
+
+fn setup_panel(mut commands: Commands) {
+    commands.spawn((
+        MutatorPanel,
+        StateScoped(GameState::Shop),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(15.0),
+            left: Val::Px(15.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(10.0),
+            ..default()
+        },
+    ));
+    commands.run_system_cached(rebuild_panel);
+}
+
+fn rebuild_panel(
+    mut commands: Commands,
+    panel: Single<Entity, With<MutatorPanel>>,
+    mutators: Res<ActiveMutators>,
+    loc: Res<Localization>,
+) {
+    commands.entity(*panel).despawn_related::<Children>();
+    commands.entity(*panel).with_children(|parent| {
+        parent.spawn(Text::new(loc.tr_args(
+            "mutators-title",
+            &[("multiplier", mutators.credit_multiplier().into())],
+        )));
+        for mutator in MUTATORS {
+            let color = if mutators.is_active(mutator.id()) {
+                Color::WHITE
+            } else {
+                Color::srgb(0.4, 0.4, 0.4)
+            };
+            parent
+                .spawn((
+                    Button,
+                    MutatorButton(mutator.id()),
+                    Node {
+                        width: Val::Px(300.0),
+                        border: UiRect::all(Val::Px(1.0)),
+                        padding: UiRect::all(Val::Px(10.0)),
+                        ..default()
+                    },
+                    BorderColor(color),
+                    BorderRadius::all(Val::Px(5.0)),
+                    children![(
+                        TextColor(color),
+                        TextFont {
+                            font_size: 14.0,
+                            ..default()
+                        },
+                        Text::new(format!(
+                            "{} (x{:.2})\n{}",
+                            loc.tr(&format!("mutator-{}", mutator.id())),
+                            mutator.credit_multiplier(),
+                            loc.tr(&format!("mutator-{}-description", mutator.id())),
+                        )),
+                    )],
+                ))
+                .observe(mutator_clicked);
+        }
+    });
+}
+
+impl Plugin for MutatorsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::InGame), setup_game)
+            .add_systems(OnEnter(GameState::Playing), apply_mutators)
+            .add_systems(OnExit(GameState::Playing), restore_scaling)
+            .add_systems(OnEnter(GameState::Shop), setup_panel)
+            .add_systems(
+                Update,
+                (
+                    charge_links
+                        .in_set(Simulation::Rules)
+                        .before(check_run)
+                        .run_if(mutator_active("link_cost")),
+                    setup_fog.run_if(mutator_active("fog")),
+                )
+                    .run_if(in_state(GameRunState::Playing)),
+            );
+    }
+}
@@ -0,0 +1,139 @@
+use bevy::{color::palettes::css, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use super::settings::Settings;
+
+pub struct PalettePlugin;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorMode {
+    #[default]
+    Default,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+    HighContrast,
+}
+
+impl ColorMode {
+    pub const ALL: &[ColorMode] = &[
+        ColorMode::Default,
+        ColorMode::Deuteranopia,
+        ColorMode::Protanopia,
+        ColorMode::Tritanopia,
+        ColorMode::HighContrast,
+    ];
+
+    /// Suffix of the `color-mode-<id>` message naming this mode.
+    pub fn id(self) -> &'static str {
+        match self {
+            ColorMode::Default => "default",
+            ColorMode::Deuteranopia => "deuteranopia",
+            ColorMode::Protanopia => "protanopia",
+            ColorMode::Tritanopia => "tritanopia",
+            ColorMode::HighContrast => "high-contrast",
+        }
+    }
+
+    pub fn next(self) -> ColorMode {
+        let index = ColorMode::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        ColorMode::ALL[(index + 1) % ColorMode::ALL.len()]
+    }
+}
+
+/// Colors for every element whose state is shown by color. States also have a
+/// non-color cue (hatched cooldown Lumina, dashed out of range line, low
+/// battery marker), so the palettes only need to keep them distinguishable.
+#[derive(Resource, Clone, Debug)]
+pub struct Palette {
+    mode: ColorMode,
+    pub lumina: LinearRgba,
+    pub lumina_cooldown: LinearRgba,
+    pub link: LinearRgba,
+    pub attach_in_range: LinearRgba,
+    pub attach_out_of_range: LinearRgba,
+    pub energy: LinearRgba,
+    pub battery_full: Color,
+    pub battery_empty: Color,
+    pub capacitor: Color,
+}
+
+impl Palette {
+    pub fn new(mode: ColorMode) -> Self {
+        let default = Palette {
+            mode,
+            lumina: LinearRgba::rgb(0.0, 0.3, 0.8),
+            lumina_cooldown: LinearRgba::rgb(0.0, 0.15, 0.4),
+            link: LinearRgba::rgb(0.0, 0.3, 0.8),
+            attach_in_range: LinearRgba::rgb(0.0, 0.15, 0.4),
+            attach_out_of_range: LinearRgba::rgb(0.1, 0.1, 0.1),
+            energy: LinearRgba::rgb(0.0, 0.3, 0.8),
+            battery_full: css::GREEN.into(),
+            battery_empty: css::RED.into(),
+            capacitor: css::DARK_ORANGE.into(),
+        };
+        match mode {
+            ColorMode::Default => default,
+            // red and green look alike, so go from blue to orange instead
+            ColorMode::Deuteranopia => Palette {
+                lumina_cooldown: LinearRgba::rgb(0.12, 0.12, 0.15),
+                battery_full: css::DODGER_BLUE.into(),
+                battery_empty: css::ORANGE.into(),
+                capacitor: css::GOLD.into(),
+                ..default
+            },
+            // reds appear dark, so the empty end is yellow
+            ColorMode::Protanopia => Palette {
+                lumina_cooldown: LinearRgba::rgb(0.12, 0.12, 0.15),
+                battery_full: css::DODGER_BLUE.into(),
+                battery_empty: css::YELLOW.into(),
+                capacitor: css::WHITE.into(),
+                ..default
+            },
+            // blue and yellow look alike, so use cyan and red
+            ColorMode::Tritanopia => Palette {
+                lumina: LinearRgba::rgb(0.0, 0.6, 0.6),
+                lumina_cooldown: LinearRgba::rgb(0.12, 0.12, 0.12),
+                link: LinearRgba::rgb(0.0, 0.6, 0.6),
+                attach_in_range: LinearRgba::rgb(0.0, 0.3, 0.3),
+                energy: LinearRgba::rgb(0.8, 0.05, 0.2),
+                battery_full: css::DARK_CYAN.into(),
+                battery_empty: css::RED.into(),
+                capacitor: css::HOT_PINK.into(),
+                ..default
+            },
+            ColorMode::HighContrast => Palette {
+                lumina: LinearRgba::rgb(1.0, 1.0, 1.0),
+                lumina_cooldown: LinearRgba::rgb(0.05, 0.05, 0.05),
+                link: LinearRgba::rgb(1.0, 1.0, 1.0),
+                attach_in_range: LinearRgba::rgb(1.0, 1.0, 0.0),
+                attach_out_of_range: LinearRgba::rgb(0.3, 0.3, 0.3),
+                energy: LinearRgba::rgb(1.0, 1.0, 0.0),
+                // the bar dims as it drains
+                battery_full: css::WHITE.into(),
+                battery_empty: css::GRAY.into(),
+                capacitor: css::YELLOW.into(),
+                ..default
+            },
+        }
+    }
+}
+
+fn setup(mut commands: Commands, settings: Res<Settings>) {
+    commands.insert_resource(Palette::new(settings.color_mode));
+}
+
+fn apply_color_mode(mut palette: ResMut<Palette>, settings: Res<Settings>) {
+    if palette.mode != settings.color_mode {
+        *palette = Palette::new(settings.color_mode);
+    }
+}
+
+impl Plugin for PalettePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreStartup, setup).add_systems(
+            PreUpdate,
+            apply_color_mode.run_if(resource_changed::<Settings>),
+        );
+    }
+}
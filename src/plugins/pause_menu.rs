@@ -2,16 +2,18 @@ use bevy::{input::common_conditions::input_just_pressed, prelude::*};
 
 use crate::GameRunState;
 
-use super::ship::Ship;
+#[cfg(not(target_arch = "wasm32"))]
+use super::graph_export::export_graph;
+use super::{localization::Localization, settings::open_settings, ship::ShipInput};
 
 pub struct PauseMenuPlugin;
 
 fn abandon_ship(
     _trigger: Trigger<Pointer<Click>>,
-    mut ship: Single<&mut Ship>,
+    mut input: ResMut<ShipInput>,
     mut commands: Commands,
 ) {
-    ship.energy = 0.0;
+    input.abandon = true;
     commands.set_state(GameRunState::Playing);
 }
 
@@ -28,7 +30,12 @@ fn quit_game(_trigger: Trigger<Pointer<Click>>, mut exit_events: EventWriter<App
     exit_events.write(AppExit::default());
 }
 
-fn button(text: &str) -> impl Bundle {
+#[cfg(not(target_arch = "wasm32"))]
+fn export_network(_trigger: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.run_system_cached(export_graph);
+}
+
+fn button(text: String) -> impl Bundle {
     (
         Button,
         Node {
@@ -46,7 +53,7 @@ fn button(text: &str) -> impl Bundle {
     )
 }
 
-fn setup(mut commands: Commands) {
+fn setup(mut commands: Commands, loc: Res<Localization>) {
     commands
         .spawn((
             StateScoped(GameRunState::Paused),
@@ -61,10 +68,23 @@ fn setup(mut commands: Commands) {
             },
         ))
         .with_children(|parent| {
-            parent.spawn(button("Abandon ship")).observe(abandon_ship);
+            parent
+                .spawn(button(loc.tr("pause-abandon")))
+                .observe(abandon_ship);
+            parent
+                .spawn(button(loc.tr("pause-settings")))
+                .observe(open_settings);
+            #[cfg(not(target_arch = "wasm32"))]
+            parent
+                .spawn(button(loc.tr("pause-export")))
+                .observe(export_network);
             #[cfg(not(target_arch = "wasm32"))]
-            parent.spawn(button("Quit game")).observe(quit_game);
-            parent.spawn(button("Continue run")).observe(resume_playing);
+            parent
+                .spawn(button(loc.tr("pause-quit")))
+                .observe(quit_game);
+            parent
+                .spawn(button(loc.tr("pause-continue")))
+                .observe(resume_playing);
         });
 }
 
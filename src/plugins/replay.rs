@@ -0,0 +1,205 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppState, GameRunState, GameState};
+
+use super::{
+    chunks::{LuminaNetwork, WorldSeed},
+    game_loop::{RunStats, Simulation, run_score, setup_run},
+    mutators::{ActiveMutators, BaseScaling, apply_mutators},
+    scaling::Scaling,
+    ship::{ExternalInput, ShipInput, read_input},
+};
+
+pub struct ReplayPlugin;
+
+/// Everything besides the player's input that decides how a run plays out.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RunSetup {
+    pub seed: u64,
+    /// Scaling before the mutators were applied.
+    pub scaling: Scaling,
+    pub mutators: Vec<String>,
+}
+
+/// Input of one simulated frame.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct ReplayInput {
+    pub thrust: Option<[f32; 2]>,
+    pub brake: bool,
+    pub abandon: bool,
+    pub waypoint: Option<[f32; 2]>,
+}
+
+/// A recorded run. Input tends to stay the same for many frames, so it is
+/// stored as runs of frames with the same input.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Replay {
+    pub setup: RunSetup,
+    /// Length of every frame in nanoseconds.
+    pub deltas: Vec<u64>,
+    /// Input and the number of frames in a row it was given for.
+    pub input: Vec<(u32, ReplayInput)>,
+}
+
+/// Input of the current run so far.
+#[derive(Resource, Default)]
+pub struct Recording {
+    deltas: Vec<u64>,
+    input: Vec<(u32, ReplayInput)>,
+}
+
+impl Recording {
+    fn push(&mut self, delta_nanos: u64, input: ReplayInput) {
+        self.deltas.push(delta_nanos);
+        match self.input.last_mut() {
+            Some((frames, last)) if *last == input => *frames += 1,
+            _ => self.input.push((1, input)),
+        }
+    }
+
+    pub fn replay(
+        &self,
+        seed: &WorldSeed,
+        base: &BaseScaling,
+        mutators: &ActiveMutators,
+    ) -> Replay {
+        Replay {
+            setup: RunSetup {
+                seed: seed.current(),
+                scaling: base.0.clone(),
+                mutators: mutators.ids().iter().map(|id| id.to_string()).collect(),
+            },
+            deltas: self.deltas.clone(),
+            input: self.input.clone(),
+        }
+    }
+}
+
+/// Replay being simulated in place of the player.
+#[derive(Resource)]
+pub struct Playback {
+    replay: Replay,
+    /// Frames played so far.
+    cursor: usize,
+    /// Input run being played and how many of its frames were.
+    run: usize,
+    played: u32,
+}
+
+fn start_recording(mut commands: Commands) {
+    commands.insert_resource(Recording::default());
+}
+
+fn record_input(time: Res<Time>, input: Res<ShipInput>, mut recording: ResMut<Recording>) {
+    recording.push(
+        time.delta().as_nanos() as u64,
+        ReplayInput {
+            thrust: input.thrust.map(|thrust| thrust.to_array()),
+            brake: input.brake,
+            abandon: input.abandon,
+            waypoint: input.waypoint.map(|waypoint| waypoint.to_array()),
+        },
+    );
+}
+
+fn load_setup(
+    playback: Res<Playback>,
+    mut scaling: ResMut<Scaling>,
+    mut mutators: ResMut<ActiveMutators>,
+) {
+    *scaling = playback.replay.setup.scaling.clone();
+    *mutators = ActiveMutators::from_ids(&playback.replay.setup.mutators);
+}
+
+fn play_input(mut playback: ResMut<Playback>, mut input: ResMut<ShipInput>) {
+    let playback = &mut *playback;
+    let Some((frames, frame)) = playback.replay.input.get(playback.run).copied() else {
+        return;
+    };
+    playback.cursor += 1;
+    playback.played += 1;
+    if playback.played == frames {
+        playback.run += 1;
+        playback.played = 0;
+    }
+    *input = ShipInput {
+        thrust: frame.thrust.map(Vec2::from_array),
+        brake: frame.brake,
+        abandon: frame.abandon,
+        waypoint: frame.waypoint.map(Vec2::from_array),
+    };
+}
+
+/// Plays a replay through the game systems of `app`, which must not have run
+/// yet, and returns the score the run ends with. Returns `None` if the run
+/// doesn't end exactly when the recording does.
+pub fn simulate(mut app: App, replay: &Replay) -> Option<u32> {
+    app.world_mut().resource_mut::<WorldSeed>().fixed = Some(replay.setup.seed);
+    app.insert_resource(ExternalInput)
+        .insert_resource(Playback {
+            replay: replay.clone(),
+            cursor: 0,
+            run: 0,
+            played: 0,
+        });
+    app.finish();
+    app.cleanup();
+    app.update();
+    app.world_mut()
+        .resource_mut::<NextState<AppState>>()
+        .set(AppState::InGame);
+
+    for delta_nanos in &replay.deltas {
+        if app
+            .world()
+            .get_resource::<State<GameRunState>>()
+            .is_some_and(|state| *state.get() == GameRunState::Ending)
+        {
+            return None;
+        }
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_nanos(
+            *delta_nanos,
+        )));
+        app.update();
+    }
+    // the run ending is only applied on the next frame
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+    app.update();
+
+    let world = app.world();
+    let ended = world
+        .get_resource::<State<GameRunState>>()
+        .is_some_and(|state| *state.get() == GameRunState::Ending);
+    if !ended || world.resource::<Playback>().cursor != replay.deltas.len() {
+        return None;
+    }
+    let stats = world.resource::<RunStats>();
+    let network = world.resource::<LuminaNetwork>();
+    Some(run_score(network.size, stats.max_distance))
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::Playing),
+            (
+                start_recording,
+                load_setup
+                    .run_if(resource_exists::<Playback>)
+                    .before(apply_mutators)
+                    .before(setup_run),
+            ),
+        )
+        .add_systems(
+            Update,
+            (play_input.run_if(resource_exists::<Playback>), record_input)
+                .chain()
+                .after(read_input)
+                .in_set(Simulation::Input)
+                .run_if(in_state(GameRunState::Playing)),
+        );
+    }
+}
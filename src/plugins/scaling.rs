@@ -1,8 +1,12 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::AppState;
 
-#[derive(Resource, Clone)]
+use super::challenge::Challenge;
+
+#[derive(Resource, Reflect, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
 pub struct Scaling {
     pub reflection_probability: f32,
     pub propagation_probability: f32,
@@ -16,6 +20,9 @@ pub struct Scaling {
     pub lumina_cooldown_per_generation: f32,
     pub lumina_resume_per_sec: f32,
     pub life_support_per_distance: f32,
+    pub can_brake: bool,
+    pub autopilot: bool,
+    pub trajectory: bool,
 }
 
 impl Default for Scaling {
@@ -33,13 +40,16 @@ impl Default for Scaling {
             lumina_cooldown_per_generation: 0.1,
             lumina_resume_per_sec: 0.33,
             life_support_per_distance: 1.0 / 30.0,
+            can_brake: true,
+            autopilot: false,
+            trajectory: false,
         }
     }
 }
 
-fn setup_game(mut commands: Commands) {
+fn setup_game(mut commands: Commands, challenge: Option<Res<Challenge>>) {
     info!("init scaling!");
-    commands.insert_resource(Scaling::default());
+    commands.insert_resource(challenge.map_or_else(Scaling::default, |c| c.scaling()));
 }
 
 pub struct ScalingPlugin;
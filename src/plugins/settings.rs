@@ -0,0 +1,407 @@
+use bevy::{
+    core_pipeline::bloom::Bloom, ecs::relationship::RelatedSpawner, ecs::spawn::SpawnWith,
+    prelude::*, window::WindowMode,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::persistence;
+
+use super::{
+    localization::{Language, Localization},
+    palette::ColorMode,
+};
+
+pub struct SettingsPlugin;
+
+const SETTINGS_SAVE: &str = "settings";
+/// Upper limits while the photosensitivity safe mode is on.
+const SAFE_BLOOM_INTENSITY: f32 = 0.1;
+const SAFE_EMISSIVE: f32 = 8.0;
+const UI_SCALES: &[Option<f32>] = &[None, Some(0.75), Some(1.0), Some(1.25), Some(1.5)];
+
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub master_volume: f32,
+    pub sfx_volume: f32,
+    pub music_volume: f32,
+    pub bloom_intensity: f32,
+    pub fullscreen: bool,
+    /// Overrides the UI scale derived from the window size.
+    pub ui_scale: Option<f32>,
+    pub reduced_motion: bool,
+    /// Limits bloom and replaces flashing feedback with a steady glow.
+    pub safe_mode: bool,
+    pub color_mode: ColorMode,
+    pub language: Language,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            master_volume: 1.0,
+            sfx_volume: 1.0,
+            music_volume: 0.6,
+            bloom_intensity: Bloom::default().intensity,
+            fullscreen: false,
+            ui_scale: None,
+            reduced_motion: false,
+            // only browsers tell us, native builds start with it off
+            safe_mode: prefers_reduced_motion(),
+            color_mode: ColorMode::default(),
+            language: Language::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn bloom(&self) -> Bloom {
+        Bloom {
+            intensity: self.effective_bloom(),
+            ..Bloom::default()
+        }
+    }
+
+    pub fn effective_bloom(&self) -> f32 {
+        if self.safe_mode {
+            self.bloom_intensity.min(SAFE_BLOOM_INTENSITY)
+        } else {
+            self.bloom_intensity
+        }
+    }
+
+    /// HDR brightness multiplier for emissive materials.
+    pub fn emissive(&self, bloom: f32) -> f32 {
+        if self.safe_mode {
+            bloom.min(SAFE_EMISSIVE)
+        } else {
+            bloom
+        }
+    }
+}
+
+/// Whether the player asked their system to reduce motion, used as the default
+/// for the safe mode. Only browsers expose this.
+#[cfg(target_arch = "wasm32")]
+fn prefers_reduced_motion() -> bool {
+    web_sys::window()
+        .and_then(|window| {
+            window
+                .match_media("(prefers-reduced-motion: reduce)")
+                .ok()
+                .flatten()
+        })
+        .is_some_and(|query| query.matches())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn prefers_reduced_motion() -> bool {
+    false
+}
+
+#[derive(States, Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[states(scoped_entities)]
+pub enum SettingsState {
+    #[default]
+    Closed,
+    Open,
+}
+
+#[derive(Component, Clone, Copy)]
+enum SettingControl {
+    MasterVolume(f32),
+    SfxVolume(f32),
+    MusicVolume(f32),
+    Bloom(f32),
+    Fullscreen,
+    UiScale,
+    ReducedMotion,
+    SafeMode,
+    ColorMode,
+    Language,
+}
+
+impl SettingControl {
+    fn apply(self, settings: &mut Settings) {
+        match self {
+            SettingControl::MasterVolume(delta) => {
+                settings.master_volume = (settings.master_volume + delta).clamp(0.0, 1.0)
+            }
+            SettingControl::SfxVolume(delta) => {
+                settings.sfx_volume = (settings.sfx_volume + delta).clamp(0.0, 1.0)
+            }
+            SettingControl::MusicVolume(delta) => {
+                settings.music_volume = (settings.music_volume + delta).clamp(0.0, 1.0)
+            }
+            SettingControl::Bloom(delta) => {
+                settings.bloom_intensity = (settings.bloom_intensity + delta).clamp(0.0, 0.5)
+            }
+            SettingControl::Fullscreen => settings.fullscreen = !settings.fullscreen,
+            SettingControl::UiScale => {
+                let index = UI_SCALES
+                    .iter()
+                    .position(|scale| *scale == settings.ui_scale)
+                    .unwrap_or(0);
+                settings.ui_scale = UI_SCALES[(index + 1) % UI_SCALES.len()];
+            }
+            SettingControl::ReducedMotion => settings.reduced_motion = !settings.reduced_motion,
+            SettingControl::SafeMode => settings.safe_mode = !settings.safe_mode,
+            SettingControl::ColorMode => settings.color_mode = settings.color_mode.next(),
+            SettingControl::Language => settings.language = settings.language.next(),
+        }
+    }
+}
+
+#[derive(Component)]
+struct SettingsRoot;
+
+pub fn open_settings(_trigger: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.set_state(SettingsState::Open);
+}
+
+fn close_settings(_trigger: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.set_state(SettingsState::Closed);
+}
+
+fn control_clicked(
+    trigger: Trigger<Pointer<Click>>,
+    control: Query<&SettingControl>,
+    mut settings: ResMut<Settings>,
+) {
+    if let Ok(control) = control.get(trigger.target()) {
+        control.apply(settings.as_mut());
+    }
+}
+
+fn small_button(text: &str, control: SettingControl) -> impl Bundle {
+    (
+        Button,
+        control,
+        Node {
+            width: Val::Px(40.0),
+            height: Val::Px(40.0),
+            border: UiRect::all(Val::Px(1.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BorderColor(Color::WHITE),
+        BorderRadius::all(Val::Px(5.0)),
+        children![Text::new(text)],
+    )
+}
+
+fn toggle_button(text: String, control: SettingControl) -> impl Bundle {
+    (
+        Button,
+        control,
+        Node {
+            width: Val::Px(180.0),
+            height: Val::Px(40.0),
+            border: UiRect::all(Val::Px(1.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BorderColor(Color::WHITE),
+        BorderRadius::all(Val::Px(5.0)),
+        children![Text::new(text)],
+    )
+}
+
+/// A labelled row with `-`/`+` buttons around the current value.
+fn stepper(label: String, value: String, down: SettingControl, up: SettingControl) -> impl Bundle {
+    (
+        Node {
+            width: Val::Px(500.0),
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        children![
+            Text::new(label),
+            (
+                Node {
+                    width: Val::Px(180.0),
+                    justify_content: JustifyContent::SpaceBetween,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                Children::spawn(SpawnWith(move |parent: &mut RelatedSpawner<ChildOf>| {
+                    parent
+                        .spawn(small_button("-", down))
+                        .observe(control_clicked);
+                    parent.spawn(Text::new(value));
+                    parent.spawn(small_button("+", up)).observe(control_clicked);
+                })),
+            )
+        ],
+    )
+}
+
+fn toggle(label: String, value: String, control: SettingControl) -> impl Bundle {
+    (
+        Node {
+            width: Val::Px(500.0),
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        Children::spawn(SpawnWith(move |parent: &mut RelatedSpawner<ChildOf>| {
+            parent.spawn(Text::new(label));
+            parent
+                .spawn(toggle_button(value, control))
+                .observe(control_clicked);
+        })),
+    )
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        SettingsRoot,
+        StateScoped(SettingsState::Open),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(15.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.95)),
+        GlobalZIndex(60),
+    ));
+    commands.run_system_cached(rebuild);
+}
+
+fn rebuild(
+    mut commands: Commands,
+    root: Single<Entity, With<SettingsRoot>>,
+    settings: Res<Settings>,
+    loc: Res<Localization>,
+) {
+    let percent =
+        |value: f32| loc.tr_args("settings-percent", &[("value", (value * 100.0).into())]);
+    let on_off = |value: bool| loc.tr(if value { "settings-on" } else { "settings-off" });
+    let ui_scale = match settings.ui_scale {
+        Some(scale) => percent(scale),
+        None => loc.tr("settings-ui-scale-auto"),
+    };
+
+    commands.entity(*root).despawn_related::<Children>();
+    commands.entity(*root).with_children(|parent| {
+        parent.spawn(Text::new(loc.tr("settings-title")));
+        parent.spawn(stepper(
+            loc.tr("settings-master-volume"),
+            percent(settings.master_volume),
+            SettingControl::MasterVolume(-0.1),
+            SettingControl::MasterVolume(0.1),
+        ));
+        parent.spawn(stepper(
+            loc.tr("settings-sfx-volume"),
+            percent(settings.sfx_volume),
+            SettingControl::SfxVolume(-0.1),
+            SettingControl::SfxVolume(0.1),
+        ));
+        parent.spawn(stepper(
+            loc.tr("settings-music-volume"),
+            percent(settings.music_volume),
+            SettingControl::MusicVolume(-0.1),
+            SettingControl::MusicVolume(0.1),
+        ));
+        parent.spawn(stepper(
+            loc.tr("settings-bloom"),
+            loc.tr_args(
+                "settings-amount",
+                &[("value", settings.bloom_intensity.into())],
+            ),
+            SettingControl::Bloom(-0.05),
+            SettingControl::Bloom(0.05),
+        ));
+        parent.spawn(toggle(
+            loc.tr("settings-fullscreen"),
+            on_off(settings.fullscreen),
+            SettingControl::Fullscreen,
+        ));
+        parent.spawn(toggle(
+            loc.tr("settings-ui-scale"),
+            ui_scale,
+            SettingControl::UiScale,
+        ));
+        parent.spawn(toggle(
+            loc.tr("settings-reduced-motion"),
+            on_off(settings.reduced_motion),
+            SettingControl::ReducedMotion,
+        ));
+        parent.spawn(toggle(
+            loc.tr("settings-safe-mode"),
+            on_off(settings.safe_mode),
+            SettingControl::SafeMode,
+        ));
+        parent.spawn(toggle(
+            loc.tr("settings-color-mode"),
+            loc.tr(&format!("color-mode-{}", settings.color_mode.id())),
+            SettingControl::ColorMode,
+        ));
+        parent.spawn(toggle(
+            loc.tr("settings-language"),
+            loc.tr("language-name"),
+            SettingControl::Language,
+        ));
+        parent
+            .spawn((
+                Button,
+                Node {
+                    width: Val::Px(150.0),
+                    height: Val::Px(65.0),
+                    border: UiRect::all(Val::Px(1.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                BorderColor(Color::WHITE),
+                BorderRadius::all(Val::Px(5.0)),
+                children![Text::new(loc.tr("settings-close"))],
+            ))
+            .observe(close_settings);
+    });
+}
+
+fn apply_settings(
+    settings: Res<Settings>,
+    mut window: Single<&mut Window>,
+    bloom: Query<&mut Bloom>,
+) {
+    let mode = if settings.fullscreen {
+        WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+    } else {
+        WindowMode::Windowed
+    };
+    if window.mode != mode {
+        window.mode = mode;
+    }
+    for mut bloom in bloom {
+        bloom.intensity = settings.effective_bloom();
+    }
+    persistence::save(SETTINGS_SAVE, settings.as_ref());
+}
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(persistence::load::<Settings>(SETTINGS_SAVE))
+            .init_state::<SettingsState>()
+            .add_systems(OnEnter(SettingsState::Open), setup)
+            .add_systems(
+                Update,
+                (
+                    apply_settings.run_if(resource_changed::<Settings>),
+                    rebuild
+                        .run_if(in_state(SettingsState::Open).and(
+                            resource_changed::<Settings>.or(resource_changed::<Localization>),
+                        )),
+                ),
+            );
+    }
+}
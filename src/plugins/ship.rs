@@ -2,9 +2,9 @@ use std::f32::consts::FRAC_PI_2;
 
 use bevy::prelude::*;
 
-use crate::GameRunState;
+use crate::{GameRunState, GameState};
 
-use super::scaling::Scaling;
+use super::{chunks::Lumina, game_loop::Simulation, palette::Palette, scaling::Scaling};
 
 #[derive(Component)]
 pub struct ShipSprite;
@@ -15,51 +15,163 @@ pub struct Ship {
     pub energy: f32,
 }
 
-fn ship_movement(
-    ship: Single<(&Transform, &mut Ship, &Camera, &GlobalTransform)>,
+/// What the player asks the ship to do this frame. Gameplay only reads input
+/// through this, so runs can be replayed from a recording.
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq)]
+pub struct ShipInput {
+    /// Direction to thrust in.
+    pub thrust: Option<Vec2>,
+    pub brake: bool,
+    pub abandon: bool,
+    /// Where the autopilot flies to while there is no manual input.
+    pub waypoint: Option<Vec2>,
+}
+
+/// Force the engines apply per second.
+const FORCE_MAGNITUDE: f32 = 500.0;
+/// Waypoints set this close to a Lumina are moved onto it.
+const WAYPOINT_SNAP: f32 = 150.0;
+/// The autopilot lets go once the ship is this close to the waypoint and
+/// slower than this.
+const ARRIVAL: f32 = 20.0;
+/// Autopilot thrust costs this much more energy than flying by hand.
+const AUTOPILOT_PENALTY: f32 = 1.3;
+const MIN_CRUISE_SPEED: f32 = 100.0;
+
+/// Set while the input comes from somewhere other than the mouse.
+#[derive(Resource)]
+pub struct ExternalInput;
+
+fn reset_input(mut commands: Commands) {
+    commands.insert_resource(ShipInput::default());
+}
+
+pub fn read_input(
+    ship: Single<(&Transform, &Camera, &GlobalTransform), With<Ship>>,
     buttons: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
     window: Single<&Window>,
-    time: Res<Time>,
+    lumina: Query<&Transform, (With<Lumina>, Without<Ship>)>,
     scaling: Res<Scaling>,
-    mut ship_sprite: Single<&mut Transform, (With<ShipSprite>, Without<Ship>)>,
+    mut input: ResMut<ShipInput>,
 ) {
-    let (ship_transform, mut ship, camera, camera_transform) = ship.into_inner();
-    let force_magnitude = 500.0;
-    let dt = time.delta_secs();
-
-    ship.energy = 0.0f32.max(
-        ship.energy
-            - ship_transform.translation.distance(Vec3::ZERO)
-                * scaling.life_support_per_distance
-                * dt,
-    );
-
+    let (ship_transform, camera, camera_transform) = ship.into_inner();
+    input.thrust = None;
+    input.brake = false;
     if let Some(world_pos) = window
         .cursor_position()
         .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor).ok())
         .map(|ray| ray.origin.truncate())
     {
-        if buttons.pressed(MouseButton::Left) {
-            let direction = (world_pos - ship_transform.translation.xy()).normalize_or_zero();
-            let force = ship.energy.min(force_magnitude * dt);
+        let waypoint_modifier = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        if waypoint_modifier {
+            if scaling.autopilot && buttons.just_pressed(MouseButton::Left) {
+                let snapped = lumina
+                    .iter()
+                    .map(|transform| transform.translation.xy())
+                    .filter(|position| position.distance(world_pos) < WAYPOINT_SNAP)
+                    .min_by(|a, b| a.distance(world_pos).total_cmp(&b.distance(world_pos)));
+                input.waypoint = Some(snapped.unwrap_or(world_pos));
+            }
+        } else if buttons.pressed(MouseButton::Left) {
+            input.thrust = Some((world_pos - ship_transform.translation.xy()).normalize_or_zero());
+            input.waypoint = None;
+        } else if buttons.pressed(MouseButton::Right) {
+            input.brake = true;
+            input.waypoint = None;
+        }
+    }
+}
+
+/// Velocity the autopilot aims for on the way to `waypoint`. Flying faster
+/// costs more energy to speed up and brake again, flying slower costs more
+/// life support on the way, so the cruise speed balances the two. Close to the
+/// waypoint the speed is limited so the ship can still brake to a stop.
+fn autopilot_velocity(
+    position: Vec2,
+    waypoint: Vec2,
+    acceleration: f32,
+    scaling: &Scaling,
+) -> Vec2 {
+    let offset = waypoint - position;
+    let distance = offset.length();
+    // minimises 2 * speed * energy_per_force + distance / speed * drain
+    let drain = position.length() * scaling.life_support_per_distance;
+    let cruise = (distance * drain / (2.0 * scaling.energy_per_force))
+        .sqrt()
+        .max(MIN_CRUISE_SPEED);
+    let stopping = 0.9 * (2.0 * acceleration * distance).sqrt();
+    offset.normalize_or_zero() * cruise.min(stopping)
+}
+
+/// Energy life support draws over `dt` at `translation`.
+pub fn life_support(translation: Vec3, dt: f32, scaling: &Scaling) -> f32 {
+    translation.distance(Vec3::ZERO) * scaling.life_support_per_distance * dt
+}
+
+/// Brakes for one frame. Returns the braking force applied.
+pub fn brake_step(linear: &mut Vec2, energy: &mut f32, dt: f32, scaling: &Scaling) -> Vec2 {
+    if linear.length_squared() <= f32::EPSILON {
+        *linear = Vec2::ZERO;
+        return Vec2::ZERO;
+    }
+    let force = energy.min(FORCE_MAGNITUDE * dt);
+    let braking_force_vector = -linear.normalize() * force;
+    *energy -= force * scaling.energy_per_force;
+    if linear.dot(*linear + braking_force_vector) < 0.0 {
+        *linear = Vec2::ZERO;
+    } else {
+        *linear += braking_force_vector;
+    }
+    braking_force_vector
+}
+
+/// Position after coasting at `linear` for `dt`.
+pub fn step_position(translation: Vec3, linear: Vec2, dt: f32) -> Vec3 {
+    translation + linear.extend(0.0) * dt
+}
+
+pub fn ship_movement(
+    ship: Single<(&Transform, &mut Ship)>,
+    mut input: ResMut<ShipInput>,
+    time: Res<Time>,
+    scaling: Res<Scaling>,
+    mut ship_sprite: Single<&mut Transform, (With<ShipSprite>, Without<Ship>)>,
+) {
+    let (ship_transform, mut ship) = ship.into_inner();
+    let ship = ship.as_mut();
+    let dt = time.delta_secs();
+
+    if input.abandon {
+        ship.energy = 0.0;
+        return;
+    }
+
+    ship.energy = 0.0f32.max(ship.energy - life_support(ship_transform.translation, dt, &scaling));
+
+    if let Some(direction) = input.thrust {
+        let force = ship.energy.min(FORCE_MAGNITUDE * dt);
+        ship.linear += direction * force;
+        ship.energy -= force * scaling.energy_per_force;
+        ship_sprite.rotation = Quat::from_rotation_z(direction.to_angle() + FRAC_PI_2);
+    } else if scaling.can_brake && input.brake {
+        let braking = brake_step(&mut ship.linear, &mut ship.energy, dt, &scaling);
+        if braking != Vec2::ZERO {
+            ship_sprite.rotation = Quat::from_rotation_z(braking.to_angle() + FRAC_PI_2);
+        }
+    } else if let Some(waypoint) = input.waypoint.filter(|_| scaling.autopilot) {
+        let position = ship_transform.translation.xy();
+        let error = autopilot_velocity(position, waypoint, FORCE_MAGNITUDE, &scaling) - ship.linear;
+        let force = error.length().min(ship.energy).min(FORCE_MAGNITUDE * dt);
+        if force > 0.0 {
+            let direction = error.normalize();
             ship.linear += direction * force;
-            ship.energy -= force * scaling.energy_per_force;
+            ship.energy -= force * scaling.energy_per_force * AUTOPILOT_PENALTY;
             ship_sprite.rotation = Quat::from_rotation_z(direction.to_angle() + FRAC_PI_2);
-        } else if buttons.pressed(MouseButton::Right) {
-            if ship.linear.length_squared() > f32::EPSILON {
-                let force = ship.energy.min(force_magnitude * dt);
-                let braking_force_vector = -ship.linear.normalize() * force;
-                ship.energy -= force * scaling.energy_per_force;
-                ship_sprite.rotation =
-                    Quat::from_rotation_z(braking_force_vector.to_angle() + FRAC_PI_2);
-                if ship.linear.dot(ship.linear + braking_force_vector) < 0.0 {
-                    ship.linear = Vec2::ZERO;
-                } else {
-                    ship.linear += braking_force_vector;
-                }
-            } else {
-                ship.linear = Vec2::ZERO;
-            }
+        }
+        if position.distance(waypoint) < ARRIVAL && ship.linear.length() < ARRIVAL {
+            ship.linear = Vec2::ZERO;
+            input.waypoint = None;
         }
     }
     if ship.energy > scaling.max_battery {
@@ -68,10 +180,20 @@ fn ship_movement(
     }
 }
 
+fn draw_waypoint(input: Res<ShipInput>, palette: Res<Palette>, mut gizmos: Gizmos) {
+    if let Some(waypoint) = input.waypoint {
+        gizmos.circle_2d(
+            Isometry2d::from_translation(waypoint),
+            ARRIVAL * 2.0,
+            palette.attach_in_range,
+        );
+    }
+}
+
 fn apply_velocity(mut query: Query<(&mut Transform, &Ship)>, time: Res<Time>) {
     let dt = time.delta_secs();
     for (mut transform, velocity) in query.iter_mut() {
-        transform.translation += velocity.linear.extend(0.0) * dt;
+        transform.translation = step_position(transform.translation, velocity.linear, dt);
     }
 }
 
@@ -85,10 +207,27 @@ pub struct ShipPlugin;
 
 impl Plugin for ShipPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            ship_movement.run_if(in_state(GameRunState::Playing)),
-        )
-        .add_systems(Update, apply_velocity.run_if(playing_or_ending));
+        app.init_resource::<ShipInput>()
+            .add_systems(OnEnter(GameState::Playing), reset_input)
+            .add_systems(
+                Update,
+                read_input
+                    .in_set(Simulation::Input)
+                    .run_if(in_state(GameRunState::Playing))
+                    .run_if(not(resource_exists::<ExternalInput>)),
+            )
+            .add_systems(
+                Update,
+                (
+                    ship_movement.run_if(in_state(GameRunState::Playing)),
+                    apply_velocity.run_if(playing_or_ending),
+                )
+                    .chain()
+                    .in_set(Simulation::Ship),
+            )
+            .add_systems(
+                Update,
+                draw_waypoint.run_if(in_state(GameRunState::Playing)),
+            );
     }
 }
@@ -2,11 +2,18 @@ use bevy::prelude::*;
 
 use crate::{AppState, GameState};
 
-use super::{game_loop::GameData, scaling::Scaling};
+use super::{game_loop::GameData, localization::Localization, scaling::Scaling};
 
 pub trait Upgrade {
-    fn description(&self, level: u32, scaling: &Scaling) -> String;
+    fn id(&self) -> &'static str;
+    fn description(&self, _level: u32, _scaling: &Scaling, loc: &Localization) -> String {
+        loc.tr(&format!("upgrade-{}", self.id()))
+    }
     fn cost(&self, level: u32) -> u32;
+    /// Highest level that can be bought, unlimited if `None`.
+    fn max_level(&self) -> Option<u32> {
+        None
+    }
     fn apply(&self, _level: u32, scaling: &mut Scaling);
     fn hidden(&self, _scaling: &Scaling, _data: &GameData) -> bool {
         false
@@ -16,8 +23,8 @@ pub trait Upgrade {
 struct BatteryUpgrade;
 
 impl Upgrade for BatteryUpgrade {
-    fn description(&self, _level: u32, _scaling: &Scaling) -> String {
-        "Research ion storage".into()
+    fn id(&self) -> &'static str {
+        "battery"
     }
     fn cost(&self, level: u32) -> u32 {
         level * 10
@@ -30,8 +37,8 @@ impl Upgrade for BatteryUpgrade {
 struct LuminaReflectionUpgrade;
 
 impl Upgrade for LuminaReflectionUpgrade {
-    fn description(&self, _level: u32, _scaling: &Scaling) -> String {
-        "Research Lumina reflection".into()
+    fn id(&self) -> &'static str {
+        "reflection"
     }
     fn cost(&self, level: u32) -> u32 {
         level * 2
@@ -47,8 +54,8 @@ impl Upgrade for LuminaReflectionUpgrade {
 struct LuminaPropagationUpgrade;
 
 impl Upgrade for LuminaPropagationUpgrade {
-    fn description(&self, _level: u32, _scaling: &Scaling) -> String {
-        "Research Lumina propagation".into()
+    fn id(&self) -> &'static str {
+        "propagation"
     }
     fn cost(&self, level: u32) -> u32 {
         level * 2
@@ -64,8 +71,8 @@ impl Upgrade for LuminaPropagationUpgrade {
 struct LuminaGenerationUpgrade;
 
 impl Upgrade for LuminaGenerationUpgrade {
-    fn description(&self, _level: u32, _scaling: &Scaling) -> String {
-        "Research Lumina generation".into()
+    fn id(&self) -> &'static str {
+        "generation"
     }
     fn cost(&self, level: u32) -> u32 {
         level * 3
@@ -78,8 +85,8 @@ impl Upgrade for LuminaGenerationUpgrade {
 struct LuminaLinksUpgrade;
 
 impl Upgrade for LuminaLinksUpgrade {
-    fn description(&self, _level: u32, _scaling: &Scaling) -> String {
-        "Research max Lumina links".into()
+    fn id(&self) -> &'static str {
+        "links"
     }
     fn cost(&self, level: u32) -> u32 {
         level * 2
@@ -95,8 +102,8 @@ impl Upgrade for LuminaLinksUpgrade {
 struct LuminaCooldownUpgrade;
 
 impl Upgrade for LuminaCooldownUpgrade {
-    fn description(&self, _level: u32, _scaling: &Scaling) -> String {
-        "Research Lumina burnout".into()
+    fn id(&self) -> &'static str {
+        "burnout"
     }
     fn cost(&self, level: u32) -> u32 {
         level * 2
@@ -112,8 +119,8 @@ impl Upgrade for LuminaCooldownUpgrade {
 struct LuminaRecoveryUpgrade;
 
 impl Upgrade for LuminaRecoveryUpgrade {
-    fn description(&self, _level: u32, _scaling: &Scaling) -> String {
-        "Research Lumina recovery".into()
+    fn id(&self) -> &'static str {
+        "recovery"
     }
     fn cost(&self, level: u32) -> u32 {
         level * 2
@@ -129,8 +136,8 @@ impl Upgrade for LuminaRecoveryUpgrade {
 struct CapacitorUpgrade;
 
 impl Upgrade for CapacitorUpgrade {
-    fn description(&self, _level: u32, _scaling: &Scaling) -> String {
-        "Research Lumina capacitors".into()
+    fn id(&self) -> &'static str {
+        "capacitor"
     }
     fn cost(&self, level: u32) -> u32 {
         level * 5
@@ -143,7 +150,47 @@ impl Upgrade for CapacitorUpgrade {
     }
 }
 
-const UPGRADES: &[&dyn Upgrade] = &[
+struct AutopilotUpgrade;
+
+impl Upgrade for AutopilotUpgrade {
+    fn id(&self) -> &'static str {
+        "autopilot"
+    }
+    fn cost(&self, level: u32) -> u32 {
+        level * 20
+    }
+    fn max_level(&self) -> Option<u32> {
+        Some(1)
+    }
+    fn apply(&self, _level: u32, scaling: &mut Scaling) {
+        scaling.autopilot = true;
+    }
+    fn hidden(&self, _scaling: &Scaling, data: &GameData) -> bool {
+        data.runs < 2
+    }
+}
+
+struct TrajectoryUpgrade;
+
+impl Upgrade for TrajectoryUpgrade {
+    fn id(&self) -> &'static str {
+        "trajectory"
+    }
+    fn cost(&self, level: u32) -> u32 {
+        level * 10
+    }
+    fn max_level(&self) -> Option<u32> {
+        Some(1)
+    }
+    fn apply(&self, _level: u32, scaling: &mut Scaling) {
+        scaling.trajectory = true;
+    }
+    fn hidden(&self, _scaling: &Scaling, data: &GameData) -> bool {
+        data.runs < 1
+    }
+}
+
+pub const UPGRADES: &[&dyn Upgrade] = &[
     &BatteryUpgrade,
     &LuminaReflectionUpgrade,
     &LuminaPropagationUpgrade,
@@ -152,21 +199,37 @@ const UPGRADES: &[&dyn Upgrade] = &[
     &LuminaCooldownUpgrade,
     &LuminaRecoveryUpgrade,
     &CapacitorUpgrade,
+    &AutopilotUpgrade,
+    &TrajectoryUpgrade,
 ];
 
 #[derive(Resource)]
-struct UpgradeLevels {
+pub struct UpgradeLevels {
     levels: [u32; UPGRADES.len()],
 }
 
+impl UpgradeLevels {
+    /// Ids and levels of every upgrade that has been bought at least once.
+    pub fn held(&self) -> Vec<(String, u32)> {
+        UPGRADES
+            .iter()
+            .zip(self.levels)
+            .filter(|(_, level)| *level > 0)
+            .map(|(upgrade, level)| (upgrade.id().to_string(), level))
+            .collect()
+    }
+}
+
+/// An upgrade as offered in the shop.
 #[derive(Component, Clone)]
-struct UpgradeState {
-    index: usize,
-    description: String,
-    level: u32,
-    cost: u32,
-    enabled: bool,
-    hidden: bool,
+pub struct UpgradeState {
+    /// Index into `UPGRADES`.
+    pub index: usize,
+    pub description: String,
+    pub level: u32,
+    pub cost: u32,
+    pub enabled: bool,
+    pub hidden: bool,
 }
 
 #[derive(Component)]
@@ -179,6 +242,10 @@ fn go_next(_trigger: Trigger<Pointer<Click>>, mut commands: Commands) {
     commands.set_state(GameState::Playing);
 }
 
+fn show_history(_trigger: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.set_state(GameState::History);
+}
+
 fn upgrade_clicked(
     trigger: Trigger<Pointer<Click>>,
     upgrade_state: Query<&UpgradeState>,
@@ -188,29 +255,46 @@ fn upgrade_clicked(
     mut levels: ResMut<UpgradeLevels>,
 ) {
     let upgrade_state = upgrade_state.get(trigger.target()).unwrap();
-    let level: u32 = levels.levels[upgrade_state.index];
-    let next_level = level + 1;
-    levels.levels[upgrade_state.index] += 1;
-    let upgrade = UPGRADES[upgrade_state.index];
-    upgrade.apply(next_level, scaling.as_mut());
-    data.network_credits -= upgrade.cost(next_level);
+    buy(upgrade_state.index, &mut scaling, &mut data, &mut levels);
 
     commands.run_system_cached(rebuild_upgrades);
     commands.run_system_cached(update_link_text);
 }
 
-fn summarise_upgrades(
+/// Buys the next level of `UPGRADES[index]`, which must be affordable.
+pub fn buy(index: usize, scaling: &mut Scaling, data: &mut GameData, levels: &mut UpgradeLevels) {
+    let level = grant(index, scaling, levels);
+    data.network_credits -= UPGRADES[index].cost(level);
+}
+
+/// Applies the next level of `UPGRADES[index]` without paying for it,
+/// returning that level.
+pub fn grant(index: usize, scaling: &mut Scaling, levels: &mut UpgradeLevels) -> u32 {
+    levels.levels[index] += 1;
+    let level = levels.levels[index];
+    UPGRADES[index].apply(level, scaling);
+    level
+}
+
+/// The upgrades on offer, visible ones first, cheapest first.
+pub fn summarise_upgrades(
     scaling: &Scaling,
     data: &GameData,
     levels: &UpgradeLevels,
+    loc: &Localization,
 ) -> Vec<UpgradeState> {
     let mut states: Vec<UpgradeState> = UPGRADES
         .iter()
         .enumerate()
+        .filter(|(index, upgrade)| {
+            upgrade
+                .max_level()
+                .is_none_or(|max| levels.levels[*index] < max)
+        })
         .map(|(index, upgrade)| UpgradeState {
             index,
             level: levels.levels[index],
-            description: upgrade.description(levels.levels[index] + 1, &scaling),
+            description: upgrade.description(levels.levels[index] + 1, &scaling, loc),
             cost: upgrade.cost(levels.levels[index] + 1),
             hidden: upgrade.hidden(&scaling, &data),
             enabled: !upgrade.hidden(&scaling, &data)
@@ -232,8 +316,9 @@ fn rebuild_upgrades(
     scaling: ResMut<Scaling>,
     data: ResMut<GameData>,
     levels: ResMut<UpgradeLevels>,
+    loc: Res<Localization>,
 ) {
-    let upgrades = summarise_upgrades(&scaling, &data, &levels);
+    let upgrades = summarise_upgrades(&scaling, &data, &levels, &loc);
     commands.entity(*parent).despawn_related::<Children>();
 
     commands.entity(*parent).with_children(|parent| {
@@ -257,13 +342,15 @@ fn rebuild_upgrades(
                 children![(
                     TextColor(color),
                     Text::new(if state.hidden {
-                        "???".into()
+                        loc.tr("shop-hidden-upgrade")
                     } else {
-                        format!(
-                            "{:}\nLevel {:}\n{:} lumina links",
-                            state.description,
-                            state.level + 1,
-                            state.cost,
+                        loc.tr_args(
+                            "shop-upgrade",
+                            &[
+                                ("description", state.description.clone().into()),
+                                ("level", (state.level + 1).into()),
+                                ("cost", state.cost.into()),
+                            ],
                         )
                     }),
                 )],
@@ -276,7 +363,7 @@ fn rebuild_upgrades(
     });
 }
 
-fn setup(mut commands: Commands, data: Res<GameData>) {
+fn setup(mut commands: Commands, data: Res<GameData>, loc: Res<Localization>) {
     commands.spawn((Camera2d, StateScoped(GameState::Shop)));
     commands
         .spawn((
@@ -298,11 +385,7 @@ fn setup(mut commands: Commands, data: Res<GameData>) {
                     },
                     TextLayout::new_with_justify(JustifyText::Center),
                     LinksText,
-                    Text::new(format!(
-                        "{:} lumina link{:}",
-                        data.network_credits,
-                        if data.network_credits == 1 { "" } else { "s" }
-                    )),
+                    Text::new(loc.tr_args("shop-links", &[("count", data.network_credits.into())])),
                 ),
                 (
                     UpgradeContainer,
@@ -334,20 +417,39 @@ fn setup(mut commands: Commands, data: Res<GameData>) {
                     },
                     BorderColor(Color::WHITE),
                     BorderRadius::all(Val::Px(5.0)),
-                    children![(Text::new("Send ship"),)],
+                    children![(Text::new(loc.tr("shop-send-ship")),)],
                 ))
                 .observe(go_next);
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        position_type: PositionType::Absolute,
+                        bottom: Val::Px(15.0),
+                        left: Val::Px(15.0),
+                        width: Val::Px(150.0),
+                        height: Val::Px(65.0),
+                        border: UiRect::all(Val::Px(1.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BorderColor(Color::WHITE),
+                    BorderRadius::all(Val::Px(5.0)),
+                    children![(Text::new(loc.tr("shop-history")),)],
+                ))
+                .observe(show_history);
         });
 
     commands.run_system_cached(rebuild_upgrades);
 }
 
-fn update_link_text(mut text: Single<&mut Text, With<LinksText>>, data: Res<GameData>) {
-    text.0 = format!(
-        "{:} lumina link{:}",
-        data.network_credits,
-        if data.network_credits == 1 { "" } else { "s" }
-    );
+fn update_link_text(
+    mut text: Single<&mut Text, With<LinksText>>,
+    data: Res<GameData>,
+    loc: Res<Localization>,
+) {
+    text.0 = loc.tr_args("shop-links", &[("count", data.network_credits.into())]);
 }
 
 fn setup_game(mut commands: Commands) {
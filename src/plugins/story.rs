@@ -1,34 +1,144 @@
-use bevy::{color::palettes::css, ecs::relationship::RelatedSpawner, prelude::*};
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    color::palettes::css,
+    ecs::relationship::RelatedSpawner,
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
 
-use crate::GameState;
+use crate::{GameRunState, GameState, persistence};
 
-use super::game_loop::GameData;
+use super::{
+    chunks::{Cooldown, Lumina},
+    game_loop::{GameData, RunStats},
+    localization::Localization,
+    mutators::ActiveMutators,
+    scaling::Scaling,
+};
 use bevy::ecs::spawn::SpawnWith;
 
 pub struct StoryPlugin;
 
-// TODO: spanning tree/cycles
-const STORIES: &[&str] = &[
-    r#"In this sector of deep space, we've uncovered a vast field of Lumina nodes. Through dedicated research, we've engineered vessels powered solely by Lumina ions. These ships can establish links between nodes and draw ions when connected. To push our breakthroughs even further, we must extend and reinforce these links.
+const STORY_LOG_SAVE: &str = "story";
 
-Left click applies a force in the direction of the mouse pointer.
+/// Condition under which a story entry is unlocked, evaluated at the end of a run.
+#[derive(Deserialize, Debug, Clone)]
+pub enum StoryTrigger {
+    /// At least this many runs have been completed.
+    Runs(u32),
+    /// A Lumina node has entered cooldown at some point in the campaign.
+    FirstCooldown,
+    /// A Lumina node has reached the link limit at some point in the campaign.
+    LinkLimitReached,
+    MaxBattery(f32),
+    /// The last run created more than this many links.
+    LastRunLinks(u32),
+    LastRunDistance(f32),
+    /// The entry with this id has already been unlocked.
+    Unlocked(String),
+    All(Vec<StoryTrigger>),
+    Any(Vec<StoryTrigger>),
+    Not(Box<StoryTrigger>),
+}
+
+struct StoryContext<'a> {
+    data: &'a GameData,
+    scaling: &'a Scaling,
+    stats: &'a RunStats,
+    log: &'a StoryLog,
+}
+
+impl StoryTrigger {
+    fn satisfied(&self, context: &StoryContext) -> bool {
+        match self {
+            StoryTrigger::Runs(runs) => context.data.runs >= *runs,
+            StoryTrigger::FirstCooldown => context.log.cooldown_seen,
+            StoryTrigger::LinkLimitReached => context.log.link_limit_seen,
+            StoryTrigger::MaxBattery(battery) => context.scaling.max_battery >= *battery,
+            StoryTrigger::LastRunLinks(links) => context.data.last_run_network_size > *links,
+            StoryTrigger::LastRunDistance(distance) => context.stats.max_distance >= *distance,
+            StoryTrigger::Unlocked(id) => context.log.unlocked.contains(id),
+            StoryTrigger::All(triggers) => triggers.iter().all(|t| t.satisfied(context)),
+            StoryTrigger::Any(triggers) => triggers.iter().any(|t| t.satisfied(context)),
+            StoryTrigger::Not(trigger) => !trigger.satisfied(context),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct StoryEntry {
+    pub id: String,
+    pub trigger: StoryTrigger,
+}
+
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct StoryAsset {
+    pub entries: Vec<StoryEntry>,
+}
+
+#[derive(Default)]
+struct StoryAssetLoader;
+
+impl AssetLoader for StoryAssetLoader {
+    type Asset = StoryAsset;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<StoryAsset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["story.ron"]
+    }
+}
+
+#[derive(Resource)]
+pub struct StoryResources {
+    pub story: Handle<StoryAsset>,
+}
+
+/// Persistent log of unlocked story entries and the campaign events they depend on.
+#[derive(Resource, Serialize, Deserialize, Default)]
+pub struct StoryLog {
+    unlocked: Vec<String>,
+    cooldown_seen: bool,
+    link_limit_seen: bool,
+}
 
-Right click applies an automatic braking force opposite the direction of motion.
+impl StoryLog {
+    /// Ids of the unlocked entries, in the order they were unlocked.
+    pub fn unlocked(&self) -> &[String] {
+        &self.unlocked
+    }
 
-Be gentle, energy is limited."#,
-    "Our ships can only draw ions when they are close to a Lumina node. We need to balance forging new links with regular stops at nodes to recharge.",
-    "Our grasp of Lumina is still in its infancy. We've learned how to trigger ion bursts by linking a ship, and watched as those ions propagate outward before folding back to their origin. The effect is striking but fickle. Each trial yields unpredictable results. With deeper study, however, we're confident we can improve the consistency.",
-    "It should be obvious that repeated ion bursts can temporarily shut down a Lumina node. While offline, the node will still propagate and reflect ions but won't generate more until the ship is detached and some amount of time has elapsed. Early efforts to work around this shutdown are promising, but further research is needed.",
-    "In this area the ship's life-support is tied to the the distance from the point of entry. We'll need to develop further efficiencies before we can venture further.",
-    "Our technology can't yet sustain unlimited links from a single Lumina node. To prevent catastrophic overloads, each node is capped at a strict connection limit. Exceeding it would result in stability collapses and a dangerous feedback loop.\n\nWe need more link data to advance our research.",
-    "No further transmissions available.\n\nContinue research.",
-];
+    /// Unlocks exactly the first `count` entries of `story`.
+    pub fn unlock_first(&mut self, story: &StoryAsset, count: usize) {
+        self.unlocked = story
+            .entries
+            .iter()
+            .take(count)
+            .map(|entry| entry.id.clone())
+            .collect();
+        persistence::save(STORY_LOG_SAVE, self);
+    }
+}
 
 #[derive(Component, Debug)]
 struct StoryUi {
     links: u32,
+    mutators: Vec<&'static str>,
+    credit_multiplier: f32,
+    /// Message ids of the pages.
+    pages: Vec<String>,
     current: usize,
-    max: usize,
 }
 
 fn shop(_trigger: Trigger<Pointer<Click>>, mut commands: Commands) {
@@ -53,12 +163,84 @@ fn prev(
     commands.run_system_cached(rebuild);
 }
 
-fn setup(mut commands: Commands, data: Res<GameData>) {
+fn load_story(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(StoryResources {
+        story: asset_server.load("campaign.story.ron"),
+    });
+    commands.insert_resource(persistence::load::<StoryLog>(STORY_LOG_SAVE));
+}
+
+fn observe_run(
+    cooldown: Query<(), Added<Cooldown>>,
+    lumina: Query<&Lumina, Changed<Lumina>>,
+    scaling: Res<Scaling>,
+    mut log: ResMut<StoryLog>,
+) {
+    if !log.cooldown_seen && !cooldown.is_empty() {
+        log.cooldown_seen = true;
+    }
+    if !log.link_limit_seen
+        && lumina
+            .iter()
+            .any(|lumina| lumina.targets.len() >= scaling.max_links)
+    {
+        log.link_limit_seen = true;
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    data: Res<GameData>,
+    scaling: Res<Scaling>,
+    stats: Res<RunStats>,
+    resources: Res<StoryResources>,
+    stories: Res<Assets<StoryAsset>>,
+    mut log: ResMut<StoryLog>,
+    mutators: Res<ActiveMutators>,
+) {
+    let mut current = 0;
+    let mut pages = Vec::new();
+    if let Some(story) = stories.get(&resources.story) {
+        let mut unlocked_now = 0;
+        for entry in story.entries.iter() {
+            if log.unlocked.contains(&entry.id) {
+                continue;
+            }
+            let context = StoryContext {
+                data: &data,
+                scaling: &scaling,
+                stats: &stats,
+                log: &log,
+            };
+            if entry.trigger.satisfied(&context) {
+                log.unlocked.push(entry.id.clone());
+                unlocked_now += 1;
+            }
+        }
+        persistence::save(STORY_LOG_SAVE, log.as_ref());
+
+        pages = log
+            .unlocked
+            .iter()
+            .map(|id| format!("story-{id}"))
+            .collect();
+        if unlocked_now == 0 {
+            // shown when a run didn't unlock anything new
+            pages.push("story-fallback".into());
+            unlocked_now = 1;
+        }
+        current = pages.len() - unlocked_now;
+    } else {
+        warn!("story asset not loaded");
+        pages.push("story-fallback".into());
+    }
     commands.spawn((
         StoryUi {
             links: data.last_run_network_size,
-            current: (data.runs as usize - 1).min(STORIES.len() - 1),
-            max: (data.runs as usize - 1).min(STORIES.len() - 1),
+            mutators: mutators.ids().to_vec(),
+            credit_multiplier: mutators.credit_multiplier(),
+            pages,
+            current,
         },
         StateScoped(GameState::Story),
         Node {
@@ -75,7 +257,7 @@ fn setup(mut commands: Commands, data: Res<GameData>) {
     commands.run_system_cached(rebuild);
 }
 
-fn next_prev_button(text: &str, enabled: bool) -> impl Bundle {
+fn next_prev_button(text: String, enabled: bool) -> impl Bundle {
     let color = if enabled {
         Color::WHITE
     } else {
@@ -97,19 +279,37 @@ fn next_prev_button(text: &str, enabled: bool) -> impl Bundle {
     )
 }
 
-fn rebuild(mut commands: Commands, query: Single<(Entity, &StoryUi), Changed<StoryUi>>) {
+fn rebuild(
+    mut commands: Commands,
+    query: Single<(Entity, &StoryUi), Changed<StoryUi>>,
+    loc: Res<Localization>,
+) {
     let (parent, story_ui) = query.into_inner();
-    let has_next = story_ui.current < story_ui.max;
+    let has_next = story_ui.current + 1 < story_ui.pages.len();
     let has_prev = story_ui.current > 0;
+    let prev_text = loc.tr("story-prev");
+    let next_text = loc.tr("story-next");
+    let mut summary = loc.tr_args("story-ship-lost", &[("count", story_ui.links.into())]);
+    if !story_ui.mutators.is_empty() {
+        let names: Vec<String> = story_ui
+            .mutators
+            .iter()
+            .map(|id| loc.tr(&format!("mutator-{id}")))
+            .collect();
+        summary.push('\n');
+        summary.push_str(&loc.tr_args(
+            "story-mutators",
+            &[
+                ("mutators", names.join(", ").into()),
+                ("multiplier", story_ui.credit_multiplier.into()),
+            ],
+        ));
+    }
 
     commands.entity(parent).despawn_related::<Children>();
     commands.entity(parent).with_children(|parent| {
         parent.spawn((
-            Text::new(format!(
-                "Ship Lost...\n{:} lumina link{:} created",
-                story_ui.links,
-                if story_ui.links == 1 { "" } else { "s" }
-            )),
+            Text::new(summary),
             Node {
                 align_self: AlignSelf::Start,
                 ..default()
@@ -128,7 +328,7 @@ fn rebuild(mut commands: Commands, query: Single<(Entity, &StoryUi), Changed<Sto
             BorderRadius::all(Val::Px(20.0)),
             BorderColor(Color::WHITE),
             children![
-                Text::new(STORIES[story_ui.current]),
+                Text::new(loc.tr(&story_ui.pages[story_ui.current])),
                 (
                     Node {
                         justify_content: JustifyContent::End,
@@ -137,11 +337,11 @@ fn rebuild(mut commands: Commands, query: Single<(Entity, &StoryUi), Changed<Sto
                         ..default()
                     },
                     Children::spawn(SpawnWith(move |parent: &mut RelatedSpawner<ChildOf>| {
-                        let mut prev_btn = parent.spawn(next_prev_button("Prev", has_prev));
+                        let mut prev_btn = parent.spawn(next_prev_button(prev_text, has_prev));
                         if has_prev {
                             prev_btn.observe(prev);
                         }
-                        let mut next_btn = parent.spawn(next_prev_button("Next", has_next));
+                        let mut next_btn = parent.spawn(next_prev_button(next_text, has_next));
                         if has_next {
                             next_btn.observe(next);
                         }
@@ -163,7 +363,7 @@ fn rebuild(mut commands: Commands, query: Single<(Entity, &StoryUi), Changed<Sto
                 },
                 BorderColor(Color::WHITE),
                 BorderRadius::all(Val::Px(5.0)),
-                children![(Text::new("Continue"),)],
+                children![(Text::new(loc.tr("story-continue")),)],
             ))
             .observe(shop);
     });
@@ -171,6 +371,10 @@ fn rebuild(mut commands: Commands, query: Single<(Entity, &StoryUi), Changed<Sto
 
 impl Plugin for StoryPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::Story), setup);
+        app.init_asset::<StoryAsset>()
+            .init_asset_loader::<StoryAssetLoader>()
+            .add_systems(Startup, load_story)
+            .add_systems(Update, observe_run.run_if(in_state(GameRunState::Playing)))
+            .add_systems(OnEnter(GameState::Story), setup);
     }
 }
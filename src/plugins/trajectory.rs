@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+
+use crate::{GameRunState, GameState};
+
+use super::{
+    game_loop::Simulation,
+    localization::Localization,
+    palette::Palette,
+    scaling::Scaling,
+    ship::{Ship, brake_step, life_support, step_position},
+};
+
+pub struct TrajectoryPlugin;
+
+/// How far ahead the coasting course is drawn.
+const COURSE_SECS: f32 = 5.0;
+/// Frame length the prediction is simulated with.
+const PREDICTION_DT: f32 = 1.0 / 60.0;
+/// Braking is given up on after this many predicted frames.
+const MAX_BRAKE_STEPS: usize = 1800;
+const LABEL_OFFSET: Vec3 = Vec3::new(0.0, 30.0, 0.0);
+
+/// Where the ship comes to rest when braking from now on.
+struct BrakePrediction {
+    path: Vec<Vec2>,
+    /// Energy used up until the ship stands still, life support included.
+    energy: f32,
+    stops: bool,
+}
+
+/// Runs `ship_movement` and `apply_velocity` as if the brake was held.
+fn predict_brake(translation: Vec3, ship: &Ship, scaling: &Scaling) -> BrakePrediction {
+    let mut translation = translation;
+    let mut linear = ship.linear;
+    let mut energy = ship.energy;
+    let mut path = vec![translation.xy()];
+    for _ in 0..MAX_BRAKE_STEPS {
+        if linear == Vec2::ZERO {
+            break;
+        }
+        energy = 0.0f32.max(energy - life_support(translation, PREDICTION_DT, scaling));
+        if energy <= 0.0 {
+            break;
+        }
+        brake_step(&mut linear, &mut energy, PREDICTION_DT, scaling);
+        translation = step_position(translation, linear, PREDICTION_DT);
+        path.push(translation.xy());
+    }
+    BrakePrediction {
+        path,
+        energy: ship.energy - energy,
+        stops: linear == Vec2::ZERO,
+    }
+}
+
+#[derive(Component)]
+struct BrakeLabel;
+
+fn setup_label(mut commands: Commands, scaling: Res<Scaling>) {
+    if !scaling.trajectory {
+        return;
+    }
+    commands.spawn((
+        BrakeLabel,
+        StateScoped(GameState::Playing),
+        Text2d::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        Visibility::Hidden,
+    ));
+}
+
+fn draw_trajectory(
+    ship: Single<(&Transform, &Ship)>,
+    label: Single<
+        (&mut Text2d, &mut Transform, &mut Visibility),
+        (With<BrakeLabel>, Without<Ship>),
+    >,
+    scaling: Res<Scaling>,
+    palette: Res<Palette>,
+    loc: Res<Localization>,
+    mut gizmos: Gizmos,
+) {
+    let (transform, ship) = ship.into_inner();
+    let (mut text, mut label_transform, mut visibility) = label.into_inner();
+    if ship.linear == Vec2::ZERO {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+
+    let position = transform.translation.xy();
+    let course = step_position(transform.translation, ship.linear, COURSE_SECS).xy();
+    gizmos.line_2d(position, course, palette.link.with_alpha(0.3));
+
+    if !scaling.can_brake {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    let prediction = predict_brake(transform.translation, ship, &scaling);
+    let color = if prediction.stops {
+        palette.attach_in_range
+    } else {
+        palette.attach_out_of_range
+    };
+    gizmos.linestrip_2d(prediction.path.iter().copied(), color);
+    let stop = prediction.path.last().copied().unwrap_or(position);
+    gizmos.circle_2d(Isometry2d::from_translation(stop), 10.0, color);
+
+    let message = if prediction.stops {
+        loc.tr_args(
+            "trajectory-brake-cost",
+            &[("energy", prediction.energy.into())],
+        )
+    } else {
+        loc.tr("trajectory-no-stop")
+    };
+    if text.0 != message {
+        text.0 = message;
+    }
+    label_transform.translation = stop.extend(transform.translation.z) + LABEL_OFFSET;
+    *visibility = Visibility::Visible;
+}
+
+impl Plugin for TrajectoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Playing), setup_label)
+            .add_systems(
+                Update,
+                // drawn from where the ship ended up this frame
+                draw_trajectory
+                    .after(Simulation::Rules)
+                    .run_if(in_state(GameRunState::Playing)),
+            );
+    }
+}
@@ -0,0 +1,271 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{GameRunState, GameState, persistence};
+
+use super::{
+    challenge::Challenge,
+    chunks::{Attached, AttachedChangeEvent, LuminaNetwork},
+    game_loop::{Simulation, check_run},
+    localization::Localization,
+    scaling::Scaling,
+    ship::{ExternalInput, Ship, ShipInput},
+};
+
+pub struct TutorialPlugin;
+
+const TUTORIAL_SAVE: &str = "tutorial";
+/// The battery is kept at least this full until the tutorial is done.
+const MIN_BATTERY: f32 = 0.25;
+const MOVING_SPEED: f32 = 100.0;
+const STOPPED_SPEED: f32 = 10.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TutorialStep {
+    Thrust,
+    Attach,
+    Link,
+    Brake,
+    Energy,
+}
+
+impl TutorialStep {
+    pub const ALL: &[TutorialStep] = &[
+        TutorialStep::Thrust,
+        TutorialStep::Attach,
+        TutorialStep::Link,
+        TutorialStep::Brake,
+        TutorialStep::Energy,
+    ];
+
+    /// Suffix of the `tutorial-<id>` message prompting for this step.
+    pub fn id(self) -> &'static str {
+        match self {
+            TutorialStep::Thrust => "thrust",
+            TutorialStep::Attach => "attach",
+            TutorialStep::Link => "link",
+            TutorialStep::Brake => "brake",
+            TutorialStep::Energy => "energy",
+        }
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Default)]
+struct TutorialProgress {
+    completed: bool,
+}
+
+/// Present for the whole run when it is played as the tutorial. Until every
+/// step is done the ship can't run out of energy, and the run doesn't count
+/// towards the leaderboards.
+#[derive(Resource)]
+pub struct Tutorial {
+    /// Index into `TutorialStep::ALL`, past the end once completed.
+    step: usize,
+    /// Ship energy on the previous frame, to notice energy coming back.
+    last_energy: f32,
+}
+
+impl Tutorial {
+    pub fn completed(&self) -> bool {
+        self.step >= TutorialStep::ALL.len()
+    }
+
+    fn current(&self) -> Option<TutorialStep> {
+        TutorialStep::ALL.get(self.step).copied()
+    }
+}
+
+pub fn tutorial_active(tutorial: Option<Res<Tutorial>>) -> bool {
+    tutorial.is_some_and(|tutorial| !tutorial.completed())
+}
+
+#[derive(Component)]
+struct TutorialPanel;
+
+#[derive(Component)]
+struct TutorialPrompt;
+
+fn load_progress(mut commands: Commands) {
+    commands.insert_resource(persistence::load::<TutorialProgress>(TUTORIAL_SAVE));
+}
+
+fn start_tutorial(
+    mut commands: Commands,
+    progress: Res<TutorialProgress>,
+    challenge: Option<Res<Challenge>>,
+    loc: Res<Localization>,
+) {
+    if progress.completed || challenge.is_some() {
+        return;
+    }
+    commands.insert_resource(Tutorial {
+        step: 0,
+        last_energy: f32::INFINITY,
+    });
+    commands
+        .spawn((
+            TutorialPanel,
+            StateScoped(GameState::Playing),
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(40.0),
+                width: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(10.0),
+                ..default()
+            },
+            children![(
+                TutorialPrompt,
+                Text::new(""),
+                TextLayout::new_with_justify(JustifyText::Center),
+                Node {
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            )],
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        padding: UiRect::axes(Val::Px(15.0), Val::Px(5.0)),
+                        border: UiRect::all(Val::Px(1.0)),
+                        ..default()
+                    },
+                    BorderColor(Color::WHITE),
+                    BorderRadius::all(Val::Px(5.0)),
+                    children![(
+                        Text::new(loc.tr("tutorial-skip")),
+                        TextFont {
+                            font_size: 14.0,
+                            ..default()
+                        },
+                    )],
+                ))
+                .observe(skip_tutorial);
+        });
+}
+
+fn end_tutorial(mut commands: Commands) {
+    commands.remove_resource::<Tutorial>();
+}
+
+fn complete(tutorial: &mut Tutorial, progress: &mut TutorialProgress) {
+    tutorial.step = TutorialStep::ALL.len();
+    progress.completed = true;
+    persistence::save(TUTORIAL_SAVE, progress);
+}
+
+fn skip_tutorial(
+    _trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut tutorial: ResMut<Tutorial>,
+    mut progress: ResMut<TutorialProgress>,
+    panel: Single<Entity, With<TutorialPanel>>,
+) {
+    complete(&mut tutorial, &mut progress);
+    commands.entity(*panel).despawn();
+}
+
+fn advance(
+    mut tutorial: ResMut<Tutorial>,
+    mut progress: ResMut<TutorialProgress>,
+    ship: Single<(&Ship, Option<&Attached>)>,
+    mut attached_events: EventReader<AttachedChangeEvent>,
+    input: Res<ShipInput>,
+    network: Res<LuminaNetwork>,
+) {
+    let (ship, attached) = ship.into_inner();
+    let in_range = attached.is_some_and(|attached| attached.in_range);
+    let hopped = attached_events.read().count() > 0;
+    let done = match tutorial.current() {
+        Some(TutorialStep::Thrust) => ship.linear.length() > MOVING_SPEED,
+        Some(TutorialStep::Attach) => in_range,
+        Some(TutorialStep::Link) => hopped && network.size > 0,
+        Some(TutorialStep::Brake) => input.brake && ship.linear.length() < STOPPED_SPEED,
+        Some(TutorialStep::Energy) => in_range && ship.energy > tutorial.last_energy,
+        None => false,
+    };
+    tutorial.last_energy = ship.energy;
+    if done {
+        tutorial.step += 1;
+        if tutorial.completed() {
+            complete(&mut tutorial, &mut progress);
+        }
+    }
+}
+
+fn keep_battery(mut ship: Single<&mut Ship>, scaling: Res<Scaling>, input: Res<ShipInput>) {
+    // abandoning the ship still ends the run
+    if !input.abandon {
+        ship.energy = ship.energy.max(scaling.max_battery * MIN_BATTERY);
+    }
+}
+
+fn update_prompt(
+    tutorial: Res<Tutorial>,
+    attached: Option<Single<&Attached>>,
+    mut prompt: Single<&mut Text, With<TutorialPrompt>>,
+    loc: Res<Localization>,
+) {
+    let text = match tutorial.current() {
+        Some(step) => step_prompt(&tutorial, step, attached.as_deref().copied(), &loc),
+        None => loc.tr("tutorial-complete"),
+    };
+    if prompt.0 != text {
+        prompt.0 = text;
+    }
+}
+
+fn step_prompt(
+    tutorial: &Tutorial,
+    step: TutorialStep,
+    attached: Option<&Attached>,
+    loc: &Localization,
+) -> String {
+    let text = format!(
+        "{}\n{}",
+        loc.tr_args(
+            "tutorial-step",
+            &[
+                ("step", (tutorial.step + 1).into()),
+                ("total", TutorialStep::ALL.len().into()),
+            ],
+        ),
+        loc.tr(&format!("tutorial-{}", step.id())),
+    );
+    if attached.is_some_and(|attached| !attached.in_range) {
+        format!("{text}\n{}", loc.tr("tutorial-out-of-range"))
+    } else {
+        text
+    }
+}
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, load_progress)
+            .add_systems(
+                OnEnter(GameState::Playing),
+                // the tutorial needs someone to follow it
+                start_tutorial.run_if(not(resource_exists::<ExternalInput>)),
+            )
+            .add_systems(OnExit(GameState::Playing), end_tutorial)
+            .add_systems(
+                Update,
+                (
+                    // energy topped up by `keep_battery` doesn't count as returned
+                    (keep_battery, advance)
+                        .chain()
+                        .in_set(Simulation::Rules)
+                        .before(check_run)
+                        .run_if(tutorial_active),
+                    update_prompt.run_if(resource_exists::<Tutorial>),
+                )
+                    .run_if(in_state(GameRunState::Playing)),
+            );
+    }
+}
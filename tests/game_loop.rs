@@ -0,0 +1,106 @@
+mod harness;
+
+use bevy::prelude::*;
+
+use bevy_jam_6::{
+    AppState, GameRunState, GameState,
+    plugins::{
+        challenge::{Challenge, ChallengeKind},
+        chunks::LuminaNetwork,
+        game_loop::GameData,
+        history::RunHistory,
+        ship::{Ship, ShipInput},
+    },
+};
+use harness::{Harness, lumina, map};
+
+fn abandon(harness: &mut Harness) {
+    harness.input(ShipInput {
+        abandon: true,
+        ..default()
+    });
+    harness.wait_for(GameRunState::Ending);
+    harness.input(ShipInput::default());
+}
+
+#[test]
+fn runs_go_through_story_and_shop() {
+    let mut harness = Harness::new(map(&[lumina(0.0, 150.0)]));
+    assert_eq!(harness.state::<AppState>(), Some(AppState::MainMenu));
+
+    harness.set_state(AppState::InGame);
+    harness.wait_for(GameState::Playing);
+    harness.wait_for(GameRunState::Playing);
+    assert_eq!(harness.resource::<GameData>().runs, 0);
+
+    abandon(&mut harness);
+    harness.wait_for(GameState::Story);
+    assert_eq!(harness.resource::<GameData>().runs, 1);
+
+    harness.set_state(GameState::Shop);
+    harness.wait_for(GameState::Shop);
+    harness.set_state(GameState::Playing);
+    harness.wait_for(GameRunState::Playing);
+    let ship = harness.ship();
+    assert!(harness.app.world().get::<Ship>(ship).unwrap().energy > 0.0);
+    assert_eq!(harness.resource::<LuminaNetwork>().size, 0);
+
+    abandon(&mut harness);
+    harness.wait_for(GameState::Story);
+    assert_eq!(harness.resource::<GameData>().runs, 2);
+}
+
+#[test]
+fn links_are_paid_out_as_credits() {
+    let mut harness = Harness::playing(map(&[lumina(0.0, 0.0), lumina(600.0, 0.0)]));
+    harness.update(1);
+    harness.teleport(Vec2::new(600.0, 0.0));
+    harness.update(2);
+    assert_eq!(harness.resource::<LuminaNetwork>().size, 1);
+
+    abandon(&mut harness);
+    let data = harness.resource::<GameData>();
+    assert_eq!(data.network_credits, 1);
+    assert_eq!(data.last_run_network_size, 1);
+}
+
+#[test]
+fn credits_carry_over_to_the_next_run() {
+    let mut harness = Harness::playing(map(&[lumina(0.0, 0.0), lumina(600.0, 0.0)]));
+    harness.update(1);
+    harness.teleport(Vec2::new(600.0, 0.0));
+    harness.update(2);
+    abandon(&mut harness);
+    harness.wait_for(GameState::Story);
+    harness.set_state(GameState::Playing);
+    harness.wait_for(GameRunState::Playing);
+
+    // the network starts over, the credits don't
+    assert_eq!(harness.resource::<LuminaNetwork>().size, 0);
+    harness.update(1);
+    harness.teleport(Vec2::new(600.0, 0.0));
+    harness.update(2);
+    abandon(&mut harness);
+    assert_eq!(harness.resource::<GameData>().network_credits, 2);
+}
+
+#[test]
+fn challenge_runs_leave_the_campaign_alone() {
+    let mut harness = Harness::new(map(&[lumina(0.0, 0.0), lumina(600.0, 0.0)]));
+    harness
+        .app
+        .insert_resource(Challenge::today(ChallengeKind::Daily));
+    harness.set_state(AppState::InGame);
+    harness.wait_for(GameRunState::Playing);
+    harness.update(1);
+    harness.teleport(Vec2::new(600.0, 0.0));
+    harness.update(2);
+    assert_eq!(harness.resource::<LuminaNetwork>().size, 1);
+
+    abandon(&mut harness);
+    harness.wait_for(GameState::ChallengeResult);
+    let data = harness.resource::<GameData>();
+    assert_eq!(data.runs, 0);
+    assert_eq!(data.network_credits, 0);
+    assert!(harness.resource::<RunHistory>().runs.is_empty());
+}
@@ -0,0 +1,152 @@
+//! Runs the game headless on a hand-authored map, so that the Lumina around
+//! the ship are exactly the ones a test places.
+
+// every test crate uses a different part of the harness
+#![allow(dead_code)]
+
+use std::time::Duration;
+
+use bevy::{prelude::*, state::state::FreelyMutableState, time::TimeUpdateStrategy};
+
+use bevy_jam_6::{
+    AppState, GameRunState, headless_app, persistence,
+    plugins::{
+        chunks::{Attached, Lumina},
+        map::{AuthoredMap, Map, MapLumina},
+        settings::Settings,
+        ship::{ExternalInput, Ship, ShipInput},
+    },
+};
+
+pub const FRAME: Duration = Duration::from_millis(16);
+/// Frames a state change may take, fades are skipped.
+const MAX_TRANSITION_FRAMES: u32 = 10;
+
+/// Map with the given Lumina, the ship starting at the origin.
+pub fn map(lumina: &[MapLumina]) -> Map {
+    Map {
+        lumina: lumina.to_vec(),
+        ..default()
+    }
+}
+
+pub fn lumina(x: f32, y: f32) -> MapLumina {
+    MapLumina {
+        position: Vec2::new(x, y),
+        kind: default(),
+    }
+}
+
+pub struct Harness {
+    pub app: App,
+}
+
+impl Harness {
+    /// Starts the game on `map` and stays in the main menu.
+    pub fn new(map: Map) -> Self {
+        persistence::disable_saving();
+        persistence::disable_loading();
+        let mut app = headless_app();
+        app.insert_resource(ExternalInput)
+            .insert_resource(AuthoredMap(map))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME));
+        app.world_mut().resource_mut::<Settings>().reduced_motion = true;
+        app.finish();
+        app.cleanup();
+        app.update();
+        Harness { app }
+    }
+
+    /// Starts the game on `map` with the first run under way.
+    pub fn playing(map: Map) -> Self {
+        let mut harness = Harness::new(map);
+        harness.set_state(AppState::InGame);
+        harness.wait_for(GameRunState::Playing);
+        harness
+    }
+
+    pub fn update(&mut self, frames: u32) {
+        for _ in 0..frames {
+            self.app.update();
+        }
+    }
+
+    pub fn state<S: States>(&self) -> Option<S> {
+        self.app
+            .world()
+            .get_resource::<State<S>>()
+            .map(|state| state.get().clone())
+    }
+
+    pub fn set_state<S: FreelyMutableState>(&mut self, state: S) {
+        self.app
+            .world_mut()
+            .resource_mut::<NextState<S>>()
+            .set(state);
+    }
+
+    /// Updates until `state` is entered, panicking if it isn't.
+    pub fn wait_for<S: States>(&mut self, state: S) {
+        for _ in 0..MAX_TRANSITION_FRAMES {
+            if self.state::<S>().as_ref() == Some(&state) {
+                return;
+            }
+            self.app.update();
+        }
+        assert_eq!(self.state::<S>(), Some(state));
+    }
+
+    pub fn resource<R: Resource>(&self) -> &R {
+        self.app.world().resource::<R>()
+    }
+
+    pub fn resource_mut<R: Resource>(&mut self) -> Mut<'_, R> {
+        self.app.world_mut().resource_mut::<R>()
+    }
+
+    pub fn input(&mut self, input: ShipInput) {
+        *self.resource_mut::<ShipInput>() = input;
+    }
+
+    pub fn ship(&mut self) -> Entity {
+        self.app
+            .world_mut()
+            .query_filtered::<Entity, With<Ship>>()
+            .single(self.app.world())
+            .expect("a run is under way")
+    }
+
+    /// Moves the ship to `position` and stops it there.
+    pub fn teleport(&mut self, position: Vec2) {
+        let ship = self.ship();
+        let mut entity = self.app.world_mut().entity_mut(ship);
+        entity.get_mut::<Transform>().unwrap().translation = position.extend(3.0);
+        entity.get_mut::<Ship>().unwrap().linear = Vec2::ZERO;
+    }
+
+    pub fn attached(&mut self) -> Option<(Entity, bool)> {
+        let ship = self.ship();
+        self.app
+            .world()
+            .get::<Attached>(ship)
+            .map(|attached| (attached.lumina, attached.in_range))
+    }
+
+    /// The Lumina at `position`, of which there must be exactly one.
+    pub fn lumina_at(&mut self, position: Vec2) -> Entity {
+        let found: Vec<Entity> = self
+            .app
+            .world_mut()
+            .query_filtered::<(Entity, &Transform), With<Lumina>>()
+            .iter(self.app.world())
+            .filter(|(_, transform)| transform.translation.xy().distance(position) < 1.0)
+            .map(|(entity, _)| entity)
+            .collect();
+        assert_eq!(found.len(), 1, "Lumina at {position}");
+        found[0]
+    }
+
+    pub fn has<C: Component>(&self, entity: Entity) -> bool {
+        self.app.world().get::<C>(entity).is_some()
+    }
+}
@@ -0,0 +1,152 @@
+mod harness;
+
+use bevy::prelude::*;
+
+use bevy_jam_6::plugins::{
+    chunks::{Cooldown, Lumina, LuminaNetwork},
+    map::{LuminaKind, Map, MapLumina},
+    scaling::Scaling,
+};
+use harness::{Harness, lumina, map};
+
+const A: Vec2 = Vec2::new(0.0, 0.0);
+const B: Vec2 = Vec2::new(600.0, 0.0);
+const C: Vec2 = Vec2::new(600.0, 600.0);
+/// Out of reach of every Lumina.
+const NOWHERE: Vec2 = Vec2::new(-600.0, -600.0);
+
+fn triangle() -> Harness {
+    let mut harness =
+        Harness::playing(map(&[lumina(A.x, A.y), lumina(B.x, B.y), lumina(C.x, C.y)]));
+    harness.update(1);
+    harness
+}
+
+fn fly_to(harness: &mut Harness, position: Vec2) {
+    harness.teleport(position);
+    harness.update(2);
+}
+
+fn targets(harness: &Harness, lumina: Entity) -> Vec<Entity> {
+    harness
+        .app
+        .world()
+        .get::<Lumina>(lumina)
+        .unwrap()
+        .targets
+        .clone()
+}
+
+#[test]
+fn ship_attaches_to_the_closest_lumina() {
+    let mut harness = Harness::playing(map(&[lumina(0.0, 150.0), lumina(0.0, -180.0)]));
+    harness.update(1);
+    let closest = harness.lumina_at(Vec2::new(0.0, 150.0));
+    assert_eq!(harness.attached(), Some((closest, true)));
+
+    // out of range the ship stays attached to the last Lumina
+    fly_to(&mut harness, NOWHERE);
+    assert_eq!(harness.attached(), Some((closest, false)));
+}
+
+#[test]
+fn flying_between_lumina_links_them() {
+    let mut harness = triangle();
+    let (a, b) = (harness.lumina_at(A), harness.lumina_at(B));
+    fly_to(&mut harness, B);
+    assert_eq!(harness.resource::<LuminaNetwork>().size, 1);
+    assert_eq!(targets(&harness, a), vec![b]);
+    assert_eq!(targets(&harness, b), vec![a]);
+
+    // flying back along a link doesn't link again
+    fly_to(&mut harness, A);
+    assert_eq!(harness.resource::<LuminaNetwork>().size, 1);
+
+    fly_to(&mut harness, C);
+    assert_eq!(harness.resource::<LuminaNetwork>().size, 2);
+}
+
+#[test]
+fn links_never_close_a_loop() {
+    let mut harness = triangle();
+    fly_to(&mut harness, B);
+    fly_to(&mut harness, C);
+    // A and C are already connected through B
+    fly_to(&mut harness, A);
+    assert_eq!(harness.resource::<LuminaNetwork>().size, 2);
+}
+
+#[test]
+fn lumina_stop_linking_at_the_limit() {
+    let mut harness = triangle();
+    harness.resource_mut::<Scaling>().max_links = 1;
+    let b = harness.lumina_at(B);
+    fly_to(&mut harness, B);
+    fly_to(&mut harness, C);
+    assert_eq!(harness.resource::<LuminaNetwork>().size, 1);
+    assert_eq!(targets(&harness, b).len(), 1);
+}
+
+#[test]
+fn authored_links_follow_the_link_rules() {
+    let mut harness = Harness::playing(Map {
+        links: vec![(0, 1), (1, 2), (2, 0), (1, 3), (1, 4)],
+        ..map(&[
+            lumina(A.x, A.y),
+            lumina(B.x, B.y),
+            lumina(C.x, C.y),
+            lumina(1200.0, 0.0),
+            lumina(600.0, -600.0),
+        ])
+    });
+    harness.update(1);
+    let (a, b, c) = (
+        harness.lumina_at(A),
+        harness.lumina_at(B),
+        harness.lumina_at(C),
+    );
+    // C and A are already connected through B
+    assert_eq!(targets(&harness, c), vec![b]);
+    assert_eq!(targets(&harness, a), vec![b]);
+    // B is at the limit before its last link
+    let max_links = harness.resource::<Scaling>().max_links;
+    assert_eq!(targets(&harness, b).len(), max_links);
+    assert_eq!(harness.resource::<LuminaNetwork>().size, 0);
+}
+
+#[test]
+fn resting_lumina_resume_once_left() {
+    let mut harness = Harness::playing(map(&[
+        MapLumina {
+            position: A,
+            kind: LuminaKind::Resting,
+        },
+        lumina(B.x, B.y),
+    ]));
+    harness.resource_mut::<Scaling>().lumina_resume_per_sec = f32::INFINITY;
+    harness.update(3);
+    let a = harness.lumina_at(A);
+    assert!(harness.has::<Cooldown>(a), "resumed while attached");
+
+    // the ship stays attached until it reaches another Lumina
+    fly_to(&mut harness, NOWHERE);
+    assert!(harness.has::<Cooldown>(a));
+    fly_to(&mut harness, B);
+    assert!(!harness.has::<Cooldown>(a));
+}
+
+#[test]
+fn generating_lumina_cool_down() {
+    let mut map = map(&[lumina(A.x, A.y), lumina(B.x, B.y)]);
+    map.links = vec![(0, 1)];
+    let mut harness = Harness::playing(map);
+    {
+        let mut scaling = harness.resource_mut::<Scaling>();
+        scaling.generation_per_sec = f32::INFINITY;
+        scaling.lumina_cooldown_per_generation = 1.0;
+    }
+    harness.update(2);
+    let (a, b) = (harness.lumina_at(A), harness.lumina_at(B));
+    assert!(harness.has::<Cooldown>(a));
+    assert!(!harness.has::<Cooldown>(b));
+}